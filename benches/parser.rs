@@ -5,6 +5,28 @@ use bencher::benchmark_main;
 use bencher::benchmark_group;
 use parser::http::request::Request;
 
+/// A head with the headers a browser typically sends, shared by the head benchmarks
+const REQUEST: &[u8] = b"\
+        GET /index.html?id=1 HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: keep-alive\r\n\
+        Pragma: no-cache\r\n\
+        Cache-Control: no-cache\r\n\
+        User-Agent: Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/83.0.4103.61 Safari/537.36\r\n\
+        Accept: text/html,application/xhtml+xml,application/xml;q=0.9,image/webp,*/*;q=0.8\r\n\
+        Accept-Encoding: gzip, deflate, br\r\n\
+        Accept-Language: en-ZA,en-GB;q=0.9,en-US;q=0.8,en;q=0.7\r\n\
+        Origin: http://local.test.tld\r\n\
+        Referer: http://local.test.tld/index.html\r\n\
+        Cookie: session=a3fWa; theme=light; language=en\r\n\
+        If-None-Match: W/\"abc\", \"def\"\r\n\
+        If-Modified-Since: Sun, 06 Nov 1994 08:49:37 GMT\r\n\
+        Content-Length: 16\r\n\
+        Content-Type: application/json\r\n\
+        \r\n\
+        {\"test\": \"data\"}\
+";
+
 fn test(b: &mut Bencher) {
 
    let data = &b"\
@@ -25,6 +47,36 @@ fn test(b: &mut Bencher) {
     parse(b, data)
 }
 
+fn test_head(b: &mut Bencher) {
+
+    let mut headers = [header::EMPTY_HEADER; 32];
+
+    // Only the head is parsed, the body that follows it is not counted
+    b.bytes = Request::new(&mut headers).parse_headers_only(REQUEST).unwrap() as u64;
+
+    b.iter(|| {
+
+        let mut headers = [header::EMPTY_HEADER; 32];
+
+        let mut req = Request::new(&mut headers);
+
+        let buffer = black_box(REQUEST);
+
+        match req.parse_headers_only(buffer) {
+            Ok(_) => {},
+            Err(e) => panic!("Something went wrong: {}", e)
+        }
+
+    });
+}
+
+fn test_head_full(b: &mut Bencher) {
+
+    b.bytes = REQUEST.len() as u64;
+
+    parse(b, REQUEST)
+}
+
 fn parse(b: &mut Bencher, buffer: &[u8]) {
 
     b.iter(|| {
@@ -35,7 +87,7 @@ fn parse(b: &mut Bencher, buffer: &[u8]) {
 
         let buffer = black_box(buffer);
 
-        match req.parse(&buffer) {
+        match req.parse(buffer) {
             Ok(_) => {},
             Err(e) => panic!("Something went wrong: {}", e)
         }
@@ -44,4 +96,5 @@ fn parse(b: &mut Bencher, buffer: &[u8]) {
 }
 
 benchmark_group!(http, test);
-benchmark_main!(http);
+benchmark_group!(head, test_head, test_head_full);
+benchmark_main!(http, head);
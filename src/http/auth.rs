@@ -0,0 +1,47 @@
+use std::borrow::Cow;
+use crate::http::parse::find_param;
+
+/// Credentials carried by an `Authorization` header
+///
+/// https://tools.ietf.org/html/rfc7235#section-4.2
+#[derive(Debug, PartialEq, Clone)]
+pub enum Credentials<'a> {
+
+    /// The `Basic` scheme, with the user-id and password decoded from base64.
+    ///
+    /// https://tools.ietf.org/html/rfc7617
+    Basic { user: Vec<u8>, pass: Vec<u8> },
+
+    /// The `Bearer` scheme, such as `Bearer abc.def.ghi`.
+    ///
+    /// https://tools.ietf.org/html/rfc6750#section-2.1
+    Bearer(&'a [u8]),
+
+    /// Any other scheme, with its credentials left as received.
+    Other { scheme: &'a [u8], token: &'a [u8] }
+}
+
+/// A challenge carried by a `WWW-Authenticate` header, such as `Digest realm="test"` or
+/// `Negotiate abc==`
+///
+/// https://tools.ietf.org/html/rfc7235#section-2.1
+#[derive(Debug, PartialEq, Clone)]
+pub struct Challenge<'a> {
+
+    /// The authentication scheme, such as `Basic`, `Bearer` or `Digest`.
+    pub scheme: &'a [u8],
+
+    /// The token68 given in place of parameters, such as `abc==` for `Negotiate abc==`.
+    pub token68: Option<&'a [u8]>,
+
+    /// The `name=value` parameters, such as `realm` and `nonce`, with quoted values unescaped.
+    pub params: Vec<(&'a [u8], Cow<'a, [u8]>)>
+}
+
+impl<'a> Challenge<'a> {
+
+    /// Returns the value of the first parameter whose name matches `name`, ignoring ASCII case.
+    pub fn param(&self, name: &str) -> Option<&[u8]> {
+        find_param(&self.params, name)
+    }
+}
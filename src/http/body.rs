@@ -0,0 +1,163 @@
+use crate::http::parse::chunk;
+use crate::http::parse::chunk_size;
+use crate::http::parse::chunk_size_line;
+use crate::http::parse::trailer_part;
+use crate::http::parse::ParserError;
+
+/// How the length of a message body is determined
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.3.3
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum BodyLength {
+
+    /// The body is exactly this many bytes long, as given by `Content-Length`.
+    Fixed(usize),
+
+    /// The body is a series of chunks ending with a zero-length chunk.
+    Chunked,
+
+    /// The body runs until the connection is closed, which only a response may do.
+    CloseDelimited,
+
+    /// The message has no body.
+    None
+}
+
+/// Iterates over the chunks of a chunked body without copying them
+///
+/// Each item is the data of one chunk, in order. Iteration ends after the last chunk, or after
+/// the first malformed chunk is reported.
+///
+/// https://tools.ietf.org/html/rfc7230#section-4.1
+#[derive(Debug, Clone)]
+pub struct ChunkedDecoder<'a> {
+
+    /// The input that has not been decoded yet.
+    input: &'a [u8],
+
+    /// The trailer fields that follow the last chunk, once it has been reached.
+    trailers: &'a [u8],
+
+    /// Whether the last chunk, or an error, has been reached.
+    done: bool
+}
+
+impl<'a> ChunkedDecoder<'a> {
+
+    /// Wraps `input`, which starts at the size line of the first chunk.
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input, trailers: b"", done: false }
+    }
+
+    /// The input that follows the chunks decoded so far.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.input
+    }
+
+    /// The trailer fields sent after the last chunk, as received, empty until it has been
+    /// decoded or when there are none.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-4.1.2
+    pub fn trailers(&self) -> &'a [u8] {
+        self.trailers
+    }
+
+    /// Fails with `BodyTooLarge` when the size declared by the chunk at `remaining()` takes a
+    /// body of `received` bytes past `max_body_bytes`.
+    ///
+    /// Checked before the chunk is read, so a declared size alone is enough to fail.
+    pub fn check_declared_size(&self, received: usize, max_body_bytes: usize) -> Result<(), ParserError> {
+        match chunk_size(self.input) {
+            Ok((_, size)) if received.checked_add(size).is_none_or(|total| total > max_body_bytes) => {
+                Err(ParserError::BodyTooLarge)
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Whether the chunk at `remaining()` is cut short by the end of the input, rather than
+    /// malformed, so that decoding can succeed once more input is available.
+    pub fn needs_more_input(&self) -> bool {
+
+        let line = match self.input.iter().position(|&b| b == b'\n') {
+            Some(line) => line,
+            None => return self.input.is_empty() || chunk_size(self.input).is_ok()
+        };
+
+        // The trailer fields of the last chunk end with an empty line
+        match chunk_size_line(self.input) {
+            Ok((_, 0)) => {
+
+                let mut lines = self.input[line + 1..].split(|&b| b == b'\n');
+
+                // The last piece has no line ending yet
+                lines.next_back();
+
+                !lines.any(|line| line.is_empty() || line == b"\r")
+            },
+            Ok((_, size)) => match chunk_length(line, size) {
+                Some(length) => self.input.len() < length,
+                None => false
+            },
+            Err(_) => false
+        }
+    }
+}
+
+impl<'a> Iterator for ChunkedDecoder<'a> {
+    type Item = Result<&'a [u8], ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+
+        if self.done {
+            return None;
+        }
+
+        match chunk(self.input) {
+            Ok((input, data)) => {
+
+                let start = self.input;
+
+                self.input = input;
+
+                if data.is_empty() {
+                    self.done = true;
+
+                    // Step over the size line again to keep the trailer fields it consumed
+                    if let Some(line) = start.iter().position(|&b| b == b'\n') {
+                        self.trailers = trailer_part(&start[line + 1..]).map(|(_, trailers)| trailers).unwrap_or_default();
+                    }
+
+                    return None;
+                }
+
+                Some(Ok(data))
+            },
+            Err(_) => {
+
+                self.done = true;
+
+                // A size made of hex digits only fails to parse when it does not fit in a usize, or
+                // when the chunk it declares could never fit in memory
+                let digits = nom::character::complete::hex_digit1::<_, (&[u8], nom::error::ErrorKind)>(self.input);
+                let line = self.input.iter().position(|&b| b == b'\n');
+
+                let overflow = match chunk_size(self.input) {
+                    Ok((_, size)) => line.is_some_and(|line| chunk_length(line, size).is_none()),
+                    Err(_) => digits.is_ok()
+                };
+
+                match overflow {
+                    true => Some(Err(ParserError::ChunkSizeOverflow)),
+                    false => Some(Err(ParserError::Body))
+                }
+            }
+        }
+    }
+}
+
+// The length of a chunk whose size line ends at `line`, data and CRLF included, `None` when it
+// does not fit in a usize
+fn chunk_length(line: usize, size: usize) -> Option<usize> {
+    line.checked_add(1).and_then(|n| n.checked_add(size)).and_then(|n| n.checked_add(2))
+}
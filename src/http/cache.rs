@@ -0,0 +1,49 @@
+/// A directive carried by a `Cache-Control` header, such as `max-age=3600`
+///
+/// Names are matched ignoring ASCII case. The field names that may follow `no-cache` and
+/// `private` are not kept.
+///
+/// https://tools.ietf.org/html/rfc7234#section-5.2
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum CacheDirective<'a> {
+
+    /// `max-age=delta-seconds`
+    MaxAge(u64),
+
+    /// `max-stale[=delta-seconds]`, without seconds any stale response is acceptable.
+    MaxStale(Option<u64>),
+
+    /// `min-fresh=delta-seconds`
+    MinFresh(u64),
+
+    /// `s-maxage=delta-seconds`
+    SMaxAge(u64),
+
+    /// `no-cache`
+    NoCache,
+
+    /// `no-store`
+    NoStore,
+
+    /// `no-transform`
+    NoTransform,
+
+    /// `only-if-cached`
+    OnlyIfCached,
+
+    /// `must-revalidate`
+    MustRevalidate,
+
+    /// `proxy-revalidate`
+    ProxyRevalidate,
+
+    /// `public`
+    Public,
+
+    /// `private`
+    Private,
+
+    /// Any other directive, or a known one with a malformed argument, with its argument as
+    /// received and the surrounding quotes removed.
+    Extension(&'a [u8], Option<&'a [u8]>)
+}
@@ -0,0 +1,50 @@
+/// Limits applied while parsing an HTTP Message
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParserConfig {
+
+    /// The longest method, such as `PROPFIND`, accepted in bytes.
+    pub max_method_bytes: usize,
+
+    /// The longest request target, such as `/events?id=1`, accepted in bytes.
+    pub max_request_target_bytes: usize,
+
+    /// The longest single header line, including its CRLF, accepted in bytes.
+    pub max_header_bytes: usize,
+
+    /// The longest header block, the sum of all header lines, accepted in bytes.
+    pub max_headers_block_bytes: usize,
+
+    /// The longest body, as declared by `Content-Length` or once chunks are decoded, accepted in bytes.
+    pub max_body_bytes: usize,
+
+    /// Whether a request line without a version, such as `GET /index.html`, is accepted as an
+    /// HTTP/0.9 simple request.
+    ///
+    /// https://tools.ietf.org/html/rfc1945#section-4.1
+    pub allow_http09: bool,
+
+    /// Whether the non-standard `Proxy-Connection` header, still sent by some legacy clients,
+    /// is taken into account when deciding if a connection stays open.
+    pub honor_proxy_connection: bool
+}
+
+impl ParserConfig {
+
+    pub fn new() -> Self {
+        Self { ..Default::default() }
+    }
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            max_method_bytes: 32,
+            max_request_target_bytes: 8192,
+            max_header_bytes: 8192,
+            max_headers_block_bytes: 65536,
+            max_body_bytes: 10485760,
+            allow_http09: false,
+            honor_proxy_connection: false
+        }
+    }
+}
@@ -0,0 +1,27 @@
+/// A timestamp carried by an HTTP date header, such as `Sun, 06 Nov 1994 08:49:37 GMT`
+///
+/// HTTP dates are always expressed in GMT, fields are ordered so that comparing two dates
+/// compares them chronologically.
+///
+/// https://tools.ietf.org/html/rfc7231#section-7.1.1.1
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct HttpDate {
+
+    /// The year, such as `1994`.
+    pub year: u16,
+
+    /// The month, `1` for January through `12` for December.
+    pub month: u8,
+
+    /// The day of the month, `1` through `31`.
+    pub day: u8,
+
+    /// The hour, `0` through `23`.
+    pub hour: u8,
+
+    /// The minute, `0` through `59`.
+    pub minute: u8,
+
+    /// The second, `0` through `60` to allow for leap seconds.
+    pub second: u8
+}
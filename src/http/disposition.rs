@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+use crate::http::parse::find_param;
+use crate::http::parse::percent_decode;
+
+/// The presentation of a body or multipart part carried by a `Content-Disposition` header, such
+/// as `attachment; filename="f.txt"`
+///
+/// https://tools.ietf.org/html/rfc6266#section-4.1
+/// https://tools.ietf.org/html/rfc7578#section-4.2
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct ContentDisposition<'a> {
+
+    /// The disposition type, such as `inline`, `attachment` or `form-data`.
+    pub disposition_type: &'a [u8],
+
+    /// The `name=value` parameters, such as `name` or `filename`, with quoted values unescaped.
+    pub params: Vec<(&'a [u8], Cow<'a, [u8]>)>
+}
+
+impl<'a> ContentDisposition<'a> {
+
+    /// Returns the value of the first parameter whose name matches `name`, ignoring ASCII case.
+    pub fn param(&self, name: &str) -> Option<&[u8]> {
+        find_param(&self.params, name)
+    }
+
+    /// The form field name given by the `name` parameter of a `form-data` part.
+    pub fn name(&self) -> Option<&[u8]> {
+        self.param("name")
+    }
+
+    /// The suggested file name, taken from the extended `filename*` parameter when it can be
+    /// decoded and from the unescaped `filename` otherwise.
+    ///
+    /// https://tools.ietf.org/html/rfc6266#section-4.3
+    pub fn filename(&self) -> Option<String> {
+
+        if let Some(filename) = self.param("filename*").and_then(ext_value) {
+            return Some(filename);
+        }
+
+        String::from_utf8(self.param("filename")?.to_vec()).ok()
+    }
+}
+
+// charset "'" [ language ] "'" value-chars, only UTF-8 and ISO-8859-1 are required to be supported
+//
+// https://tools.ietf.org/html/rfc5987#section-3.2.1
+fn ext_value(input: &[u8]) -> Option<String> {
+
+    let mut parts = input.splitn(3, |&b| b == b'\'');
+
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let value = percent_decode(parts.next()?).ok()?;
+
+    match charset {
+        charset if charset.eq_ignore_ascii_case(b"UTF-8") => String::from_utf8(value).ok(),
+        charset if charset.eq_ignore_ascii_case(b"ISO-8859-1") => Some(value.iter().map(|&b| b as char).collect()),
+        _ => None
+    }
+}
@@ -0,0 +1,52 @@
+/// An entity-tag carried by an `ETag` header, such as `W/"abc"`
+///
+/// https://tools.ietf.org/html/rfc7232#section-2.3
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct ETag<'a> {
+
+    /// Whether the tag is weak, marked with a `W/` prefix.
+    pub weak: bool,
+
+    /// The opaque tag without its quotes, such as `abc`.
+    pub tag: &'a [u8]
+}
+
+impl<'a> ETag<'a> {
+
+    /// Both tags are strong and identical.
+    ///
+    /// https://tools.ietf.org/html/rfc7232#section-2.3.2
+    pub fn strong_eq(&self, other: &ETag<'_>) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// The tags are identical, whether or not either is weak.
+    pub fn weak_eq(&self, other: &ETag<'_>) -> bool {
+        self.tag == other.tag
+    }
+}
+
+/// The condition carried by an `If-None-Match` header
+///
+/// https://tools.ietf.org/html/rfc7232#section-3.2
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum IfNoneMatch<'a> {
+
+    /// `*`, matching any current representation.
+    Any,
+
+    /// A list of entity-tags.
+    Tags(Vec<ETag<'a>>)
+}
+
+impl<'a> IfNoneMatch<'a> {
+
+    /// Whether `etag` is matched, using the weak comparison, in which case the condition is
+    /// false and the request should be answered with `304 Not Modified`.
+    pub fn matches(&self, etag: &ETag<'_>) -> bool {
+        match *self {
+            IfNoneMatch::Any => true,
+            IfNoneMatch::Tags(ref tags) => tags.iter().any(|tag| tag.weak_eq(etag))
+        }
+    }
+}
@@ -1,6 +1,35 @@
+use std::borrow::Cow;
+
 /// Empty header used to initialize headers
 pub const EMPTY_HEADER: Header<'static> = Header { name: b"", value: b"" };
 
+/// Conventional casing of well-known header names
+static CANONICAL_NAMES: [&[u8]; 48] = [
+    b"Accept", b"Accept-Charset", b"Accept-Encoding", b"Accept-Language", b"Accept-Ranges",
+    b"Age", b"Allow", b"Authorization", b"Cache-Control", b"Connection", b"Content-Disposition",
+    b"Content-Encoding", b"Content-Language", b"Content-Length", b"Content-Location",
+    b"Content-Range", b"Content-Type", b"Cookie", b"Date", b"ETag", b"Expect", b"Expires",
+    b"Forwarded", b"From", b"Host", b"If-Match", b"If-Modified-Since", b"If-None-Match",
+    b"If-Range", b"If-Unmodified-Since", b"Last-Modified", b"Location", b"Max-Forwards",
+    b"Origin", b"Pragma", b"Proxy-Authenticate", b"Proxy-Authorization", b"Range", b"Referer",
+    b"Retry-After", b"Server", b"Set-Cookie", b"TE", b"Trailer", b"Transfer-Encoding",
+    b"Upgrade", b"User-Agent", b"WWW-Authenticate",
+];
+
+/// How a header name is compared when looking a header up
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum HeaderMatch {
+
+    /// The names must be the same bytes, as when verifying a signature over the header block.
+    Exact,
+
+    /// The names may differ in ASCII case, as the specification allows.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.2
+    #[default]
+    CaseInsensitive
+}
+
 #[derive(Debug, Default, PartialEq, Copy, Clone)]
 pub struct Header<'a> {
 
@@ -29,4 +58,35 @@ impl<'a> Header<'a> {
     pub fn value(&self) -> &[u8] {
         self.value
     }
+
+    /// Whether both the name and value are empty, as in `EMPTY_HEADER`.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.value.is_empty()
+    }
+
+    /// Compares the header name with `other`, ignoring ASCII case.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.2
+    pub fn name_eq(&self, other: &[u8]) -> bool {
+        self.name.eq_ignore_ascii_case(other)
+    }
+
+    /// Compares the header name with `other` as `mode` requires.
+    pub fn name_matches(&self, other: &[u8], mode: HeaderMatch) -> bool {
+        match mode {
+            HeaderMatch::Exact => self.name == other,
+            HeaderMatch::CaseInsensitive => self.name_eq(other)
+        }
+    }
+
+    /// The header name in its conventional casing, such as `Content-Type` for `content-type`.
+    ///
+    /// Names missing from the table of well-known headers are returned unchanged.
+    pub fn canonical_name(&self) -> Cow<'a, [u8]> {
+
+        match CANONICAL_NAMES.iter().find(|&&name| self.name_eq(name)) {
+            Some(&name) => Cow::Borrowed(name),
+            None => Cow::Borrowed(self.name)
+        }
+    }
 }
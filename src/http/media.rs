@@ -0,0 +1,26 @@
+use std::borrow::Cow;
+use crate::http::parse::find_param;
+
+/// A media type carried by a `Content-Type` header, such as `text/html; charset=utf-8`
+///
+/// https://tools.ietf.org/html/rfc7231#section-3.1.1.1
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct ContentType<'a> {
+
+    /// The top-level type, such as `text` for `text/html`.
+    pub media_type: &'a [u8],
+
+    /// The subtype, such as `html` for `text/html`.
+    pub subtype: &'a [u8],
+
+    /// The `name=value` parameters, such as `charset`, with quoted values unescaped.
+    pub params: Vec<(&'a [u8], Cow<'a, [u8]>)>
+}
+
+impl<'a> ContentType<'a> {
+
+    /// Returns the value of the first parameter whose name matches `name`, ignoring ASCII case.
+    pub fn param(&self, name: &str) -> Option<&[u8]> {
+        find_param(&self.params, name)
+    }
+}
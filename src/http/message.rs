@@ -0,0 +1,34 @@
+use crate::http::request::Request;
+use crate::http::response::Response;
+
+/// Either kind of HTTP Message, as seen by a proxy relaying both directions
+///
+/// https://tools.ietf.org/html/rfc7230#section-3
+#[derive(Debug)]
+pub enum Message<'a> {
+
+    /// A message that starts with a request line, such as `GET / HTTP/1.1`.
+    Request(Request<'a>),
+
+    /// A message that starts with a status line, such as `HTTP/1.1 200 OK`.
+    Response(Response<'a>)
+}
+
+impl<'a> Message<'a> {
+
+    /// The request, when the message is one.
+    pub fn request(&self) -> Option<&Request<'a>> {
+        match *self {
+            Message::Request(ref request) => Some(request),
+            Message::Response(_) => None
+        }
+    }
+
+    /// The response, when the message is one.
+    pub fn response(&self) -> Option<&Response<'a>> {
+        match *self {
+            Message::Request(_) => None,
+            Message::Response(ref response) => Some(response)
+        }
+    }
+}
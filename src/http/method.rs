@@ -0,0 +1,97 @@
+use std::fmt;
+use std::str::FromStr;
+use std::convert::Infallible;
+
+/// An HTTP request method, such as `GET`
+///
+/// Methods are case-sensitive, `get` is an extension method rather than `GET`.
+///
+/// https://tools.ietf.org/html/rfc7231#section-4
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Method {
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.1
+    Get,
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.2
+    Head,
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.3
+    Post,
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.4
+    Put,
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.5
+    Delete,
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.6
+    Connect,
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.7
+    Options,
+
+    /// https://tools.ietf.org/html/rfc7231#section-4.3.8
+    Trace,
+
+    /// https://tools.ietf.org/html/rfc5789#section-2
+    Patch,
+
+    /// Any other method, such as `PROPFIND`.
+    Extension(String)
+}
+
+impl Method {
+
+    /// Whether the method is read-only, so a request using it has no intended side effects.
+    ///
+    /// https://tools.ietf.org/html/rfc7231#section-4.2.1
+    pub fn is_safe(&self) -> bool {
+        matches!(*self, Method::Get | Method::Head | Method::Options | Method::Trace)
+    }
+
+    /// Whether sending a request using the method several times has the same intended effect
+    /// as sending it once, so it can be retried.
+    ///
+    /// https://tools.ietf.org/html/rfc7231#section-4.2.2
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(*self, Method::Put | Method::Delete)
+    }
+}
+
+impl FromStr for Method {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "GET" => Method::Get,
+            "HEAD" => Method::Head,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "DELETE" => Method::Delete,
+            "CONNECT" => Method::Connect,
+            "OPTIONS" => Method::Options,
+            "TRACE" => Method::Trace,
+            "PATCH" => Method::Patch,
+            _ => Method::Extension(s.to_string())
+        })
+    }
+}
+
+// Allow the use of "{}" when printing Method
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Method::Get => write!(f, "GET"),
+            Method::Head => write!(f, "HEAD"),
+            Method::Post => write!(f, "POST"),
+            Method::Put => write!(f, "PUT"),
+            Method::Delete => write!(f, "DELETE"),
+            Method::Connect => write!(f, "CONNECT"),
+            Method::Options => write!(f, "OPTIONS"),
+            Method::Trace => write!(f, "TRACE"),
+            Method::Patch => write!(f, "PATCH"),
+            Method::Extension(ref method) => write!(f, "{}", method)
+        }
+    }
+}
@@ -1,4 +1,19 @@
+pub mod auth;
+pub mod body;
+pub mod date;
+pub mod etag;
+pub mod cache;
+pub mod media;
 pub mod parse;
+pub mod range;
+pub mod config;
 pub mod header;
+pub mod method;
+pub mod parser;
+pub mod status;
+pub mod target;
+pub mod message;
 pub mod request;
+pub mod version;
 pub mod response;
+pub mod disposition;
@@ -1,130 +1,1426 @@
 use nom;
-use std::num;
 use std::fmt;
+use std::num;
 use nom::IResult;
+use std::borrow::Cow;
 use std::error::Error;
+use crate::http::etag::ETag;
+use crate::http::date::HttpDate;
 use crate::http::header::Header;
+use crate::http::auth::Challenge;
+use crate::http::message::Message;
+use crate::http::request::Request;
+use crate::http::version::Version;
+use crate::http::auth::Credentials;
+use crate::http::etag::IfNoneMatch;
+use crate::http::media::ContentType;
+use crate::http::response::Response;
+use crate::http::range::ContentRange;
+use crate::http::config::ParserConfig;
+use crate::http::cache::CacheDirective;
+use crate::http::target::RequestTarget;
+use crate::http::disposition::ContentDisposition;
+
+/// Method, path, version and CRLF as matched by `request_line`
+type RequestLine<'a> = (&'a [u8], &'a [u8], &'a [u8], &'a [u8]);
+
+/// Name and value of a `name=value` parameter as matched by `auth_param`
+type Param<'a> = (&'a [u8], Cow<'a, [u8]>);
+
+/// Version, status code and reason as matched by `status_line`
+type StatusLine<'a> = (&'a [u8], u16, &'a [u8]);
+
+/// Parses [RFC7230] compliant HTTP Messages<br>
+/// https://tools.ietf.org/html/rfc7230
+///
+/// # Reference
+/// * `OCTET`   - any 8-bit sequence of data<br>
+/// * `CHAR`    - any US-ASCII character (octets 0 - 127)<br>
+/// * `UPALPHA` - any US-ASCII uppercase letter "A".."Z"<br>
+/// * `LOALPHA` - any US-ASCII lowercase letter "a".."z"<br>
+/// * `ALPHA`   - UPALPHA | LOALPHA<br>
+/// * `DIGIT`   - any US-ASCII digit "0".."9"<br>
+/// * `CTL`     - any US-ASCII control character (octets 0 - 31) and DEL (127)<br>
+/// * `CR`      - US-ASCII CR, carriage return (13)<br>
+/// * `LF`      - US-ASCII LF, linefeed (10)<br>
+/// * `SP`      - US-ASCII SP, space (32)<br>
+/// * `HT`      - US-ASCII HT, horizontal-tab (9)<br>
+/// * `"`       - US-ASCII double-quote mark (34)<br>
+static TOKEN_MAP: [bool; 256] = byte_map![
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 1, 0, 1, 1, 1, 1, 1, 0, 0, 1, 1, 0, 1, 1, 0,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0,
+    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 1, 0, 1, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// Valid field-content bytes: VCHAR, SP, HT and obs-text<br>
+/// https://tools.ietf.org/html/rfc7230#section-3.2
+static HEADER_VALUE_MAP: [bool; 256] = byte_map![
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+];
+
+/// Parse HTTP Request Line
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// Method SP request-target/path SP HTTP-Version CRLF
+///
+/// A bare LF is also accepted as the line terminator.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.1.1
+pub fn request_line(input: &[u8]) -> IResult<&[u8], RequestLine<'_>> {
+    nom::sequence::tuple((method, path, version, crlf_or_lf))(input)
+}
+
+/// Parse HTTP Status Line
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// HTTP-Version SP status-code [ SP reason-phrase ] CRLF
+///
+/// The reason phrase is optional, as some servers send `HTTP/1.1 200` CRLF, and is returned as
+/// an empty slice when absent. A bare LF is also accepted as the line terminator.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.1.2
+pub fn status_line(input: &[u8]) -> IResult<&[u8], StatusLine<'_>> {
+
+    let (input, version) = version(input)?;
+
+    let (input, _) = nom::bytes::complete::tag(" ")(input)?;
+
+    let (input, status) = nom::combinator::map_opt(nom::bytes::complete::take(3usize), |digits: &[u8]| {
+        match digits.iter().all(u8::is_ascii_digit) {
+            true => std::str::from_utf8(digits).ok()?.parse::<u16>().ok(),
+            false => None
+        }
+    })(input)?;
+
+    let (input, reason) = nom::combinator::opt(nom::sequence::preceded(
+        nom::bytes::complete::tag(" "),
+        nom::bytes::complete::take_while(|b| b != b'\r' && b != b'\n'),
+    ))(input)?;
+
+    let (input, _) = crlf_or_lf(input)?;
+
+    Ok((input, (version, status, reason.unwrap_or(b""))))
+}
+
+/// Parse HTTP/0.9 Simple-Request Line
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// Method SP Request-URI CRLF
+///
+/// https://tools.ietf.org/html/rfc1945#section-4.1
+pub fn simple_request_line(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8])> {
+    nom::sequence::terminated(
+        nom::sequence::pair(
+            method,
+            nom::sequence::preceded(nom::bytes::complete::tag(" "), nom::bytes::complete::is_not(" \r\n")),
+        ),
+        crlf_or_lf,
+    )(input)
+}
+
+/// Check an HTTP Request Line that is not complete yet
+///
+/// # Arguments
+/// * `input` - A slice that holds the start of the request line, without a line terminator
+/// * `config` - The limits on the method and request target
+///
+/// Returns `Partial` while the line can still become valid, or fails with `MethodTooLong` or
+/// `RequestTargetTooLong` once what has been received already exceeds those limits, so that
+/// an unterminated line cannot be buffered without bound.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.1.1
+pub fn request_line_partial(input: &[u8], config: &ParserConfig) -> Result<Status, ParserError> {
+
+    // The version and line terminator that follow the target, as in ` HTTP/1.1` CRLF
+    let version = b" HTTP/1.1\r".len();
+
+    match input.iter().position(|&b| b == b' ') {
+        None if input.len() > config.max_method_bytes => Err(ParserError::MethodTooLong),
+        Some(method) if input.len() - method - 1 > config.max_request_target_bytes + version => {
+            Err(ParserError::RequestTargetTooLong)
+        },
+        _ => Ok(Status::Partial)
+    }
+}
+
+/// Parse HTTP Header
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+/// * `header` - A mutable instance of the Header struct
+///
+/// # Expected Format
+/// Header-Name: OWS Header Value OWS CRLF
+///
+/// The value may be empty, as in `X-Empty:` CRLF. A bare LF is accepted in place of CRLF.
+/// The OWS around the value is not part of it and is left out.
+///
+/// https://tools.ietf.org/html/rfc7231#section-4
+pub fn header<'i>(input: &'i [u8], header: &mut Header<'i>) -> nom::IResult<&'i [u8], ()> {
+
+    let line = &input[..input.iter().position(|&b| b == b'\n').unwrap_or(input.len())];
+
+    let (input, name) = nom::bytes::complete::take_while(is_token)(input)?;
+
+    // A byte that is not allowed in a field-name before the colon fails the whole block, a line
+    // without a colon is merely not a header
+    if name.is_empty() || !input.starts_with(b":") {
+        return match line.contains(&b':') {
+            true => Err(nom::Err::Failure((input, nom::error::ErrorKind::Char))),
+            false => Err(nom::Err::Error((input, nom::error::ErrorKind::Char)))
+        };
+    }
+
+    let (input, _) = nom::character::complete::char(':')(input)?;
+
+    let (input, _) = ows(input)?;
+
+    let (input, value) = nom::bytes::complete::take_while(is_header_value_token)(input)?;
+
+    // Anything other than a line ending after the value is a byte that is not allowed in
+    // field-content
+    if !input.is_empty() && crlf_or_lf(input).is_err() {
+        return Err(nom::Err::Failure((input, nom::error::ErrorKind::Verify)));
+    }
+
+    let (input, _) = crlf_or_lf(input)?;
+
+    // Trailing OWS is matched along with the value, as field-content may hold inner whitespace
+    let trailing = value.iter().rev().take_while(|&&b| is_whitespace(b)).count();
+
+    header.name = name;
+    header.value = &value[..value.len() - trailing];
+
+    Ok((input, ()))
+}
+
+/// Parse HTTP Body
+///
+/// # Arguments
+/// * `length` - Size of input to parse
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// *OCTET
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.3
+pub fn body(length: usize, input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::bytes::complete::take(length)(input)
+}
+
+/// Parse HTTP Content-Length header value
+///
+/// # Arguments
+/// * `value` - A slice that holds the header value
+///
+/// # Expected Format
+/// 1*DIGIT
+///
+/// Fails with `ContentLength` when the value is empty, holds anything but digits, or does not
+/// fit in a `usize`.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.3.2
+pub fn content_length(value: &[u8]) -> Result<usize, ParserError> {
+
+    if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+        return Err(ParserError::ContentLength);
+    }
+
+    value.iter().try_fold(0usize, |length, &b| {
+        length.checked_mul(10)?.checked_add((b - b'0') as usize)
+    }).ok_or(ParserError::ContentLength)
+}
+
+/// Parse HTTP Chunk
+///
+/// # Arguments
+/// * `input` - A slice that holds the chunked body
+///
+/// # Expected Format
+/// chunk-size [ chunk-ext ] CRLF chunk-data CRLF
+///
+/// The last chunk has a size of 0 and yields an empty slice, the trailer fields that follow it
+/// are consumed along with it. A bare LF is accepted in place of CRLF.
+///
+/// https://tools.ietf.org/html/rfc7230#section-4.1
+pub fn chunk(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+
+    let (input, size) = chunk_size_line(input)?;
+
+    let (input, data) = match size {
+        0 => trailer_part(input).map(|(input, _)| (input, &input[..0]))?,
+        size => nom::bytes::complete::take(size)(input)?
+    };
+
+    let (input, _) = crlf_or_lf(input)?;
+
+    Ok((input, data))
+}
+
+/// Parse HTTP Chunk size line
+///
+/// # Arguments
+/// * `input` - A slice that holds the chunk
+///
+/// # Expected Format
+/// chunk-size [ chunk-ext ] CRLF<br>
+/// chunk-ext = *( BWS ";" BWS chunk-ext-name [ BWS "=" BWS chunk-ext-val ] )
+///
+/// Chunk extensions carry no meaning for the parser, they are checked and discarded.
+///
+/// https://tools.ietf.org/html/rfc7230#section-4.1.1
+pub fn chunk_size_line(input: &[u8]) -> nom::IResult<&[u8], usize> {
+
+    let (input, size) = chunk_size(input)?;
+
+    let (input, _) = nom::multi::many0(nom::sequence::tuple((
+        bws,
+        nom::character::complete::char(';'),
+        bws,
+        token,
+        nom::combinator::opt(nom::sequence::tuple((
+            bws,
+            nom::character::complete::char('='),
+            bws,
+            param_value
+        )))
+    )))(input)?;
+
+    let (input, _) = crlf_or_lf(input)?;
+
+    Ok((input, size))
+}
+
+/// Parse HTTP Trailer Part
+///
+/// # Arguments
+/// * `input` - A slice that starts after the last chunk
+///
+/// # Expected Format
+/// *( header-field CRLF )
+///
+/// Returns the trailer fields as received, up to the empty line that ends the chunked body,
+/// which is left unconsumed. They can be parsed with `headers_iterator`.
+///
+/// https://tools.ietf.org/html/rfc7230#section-4.1.2
+pub fn trailer_part(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+
+    let start = input;
+    let mut input = input;
+    let mut field = Header::new();
+
+    while crlf_or_lf(input).is_err() {
+
+        let (i, _) = header(input, &mut field)?;
+
+        input = i;
+    }
+
+    Ok((input, &start[..start.len() - input.len()]))
+}
+
+/// Parse HTTP Chunked Body
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// *chunk last-chunk CRLF
+///
+/// Returns the chunked body as received, chunk framing included.
+///
+/// https://tools.ietf.org/html/rfc7230#section-4.1
+pub fn chunked_body(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+
+    let start = input;
+    let mut input = input;
+
+    loop {
+
+        let (i, data) = chunk(input)?;
+
+        input = i;
+
+        if data.is_empty() {
+            break;
+        }
+    }
+
+    Ok((input, &start[..start.len() - input.len()]))
+}
+
+/// Parse HTTP Chunk size
+///
+/// # Arguments
+/// * `input` - A slice that holds the chunk
+///
+/// # Expected Format
+/// 1*HEXDIG
+///
+/// https://tools.ietf.org/html/rfc7230#section-4.1
+pub fn chunk_size(input: &[u8]) -> nom::IResult<&[u8], usize> {
+    nom::combinator::map_res(nom::character::complete::hex_digit1, from_hex)(input)
+}
+
+fn from_hex(input: &[u8]) -> Result<usize, ParserError> {
+    Ok(usize::from_str_radix(std::str::from_utf8(input)?, 16)?)
+}
+
+/// Parse HTTP Headers
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+/// * `headers` - The slice or Vec of Header structs to fill
+/// * `config` - The limits to enforce on each header and on the header block
+///
+/// Consumes the empty line that ends the header block and returns the input that follows it.
+/// A line that is not a header fails with `Headers`, rather than ending the block early.
+/// `TooManyHeaders` is returned when `headers` is full and another header follows, a `Vec`
+/// grows instead and is never full.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.2
+pub fn headers_iterator<'i, S>(input: &'i [u8], headers: &mut S, config: &ParserConfig) -> Result<&'i [u8], ParserError>
+where
+    S: HeaderSlots<'i> + ?Sized
+{
+
+    // The end of the input is accepted in place of the empty line
+    match header_lines(input, headers, config)? {
+        (input, BlockEnd::EmptyLine) | (input, BlockEnd::Incomplete) => Ok(input),
+        (_, BlockEnd::Full) => Err(ParserError::TooManyHeaders),
+        (_, BlockEnd::Other) => Err(ParserError::Headers)
+    }
+}
+
+/// Parse HTTP Headers from a buffer that may not hold all of them yet
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+/// * `headers` - The slice or Vec of Header structs to fill
+/// * `config` - The limits to enforce on each header and on the header block
+///
+/// Returns the input that follows the empty line ending the header block, or `None` when the
+/// input ends before it. Only complete header lines are stored, so parsing again once more
+/// input is available starts over cleanly.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.2
+pub fn headers_partial<'i, S>(input: &'i [u8], headers: &mut S, config: &ParserConfig) -> Result<Option<&'i [u8]>, ParserError>
+where
+    S: HeaderSlots<'i> + ?Sized
+{
+
+    match header_lines(input, headers, config)? {
+        (input, BlockEnd::EmptyLine) => Ok(Some(input)),
+        (input, BlockEnd::Incomplete) if input.len() > config.max_header_bytes => Err(ParserError::HeaderTooLarge),
+        (_, BlockEnd::Incomplete) => Ok(None),
+        (_, BlockEnd::Full) => Err(ParserError::TooManyHeaders),
+        (_, BlockEnd::Other) => Err(ParserError::Headers)
+    }
+}
+
+// Where a run of header lines stopped
+enum BlockEnd {
+
+    // The empty line ending the header block was consumed
+    EmptyLine,
+
+    // The next line has no line feed yet
+    Incomplete,
+
+    // Every header slot is filled and another header follows
+    Full,
+
+    // The next line is not a header
+    Other
+}
+
+/// Storage that parsed headers are written to, in order
+pub trait HeaderSlots<'i> {
+
+    /// Stores `header` as the header at `index`, returning `false` when there is no room for it.
+    fn store(&mut self, index: usize, header: Header<'i>) -> bool;
+}
+
+// A fixed slice is full once every entry holds a header
+impl<'i> HeaderSlots<'i> for [Header<'i>] {
+    fn store(&mut self, index: usize, header: Header<'i>) -> bool {
+        match self.get_mut(index) {
+            Some(slot) => {
+
+                *slot = header;
+
+                true
+            },
+            None => false
+        }
+    }
+}
+
+// A Vec grows as needed
+impl<'i> HeaderSlots<'i> for Vec<Header<'i>> {
+    fn store(&mut self, index: usize, header: Header<'i>) -> bool {
+
+        self.truncate(index);
+        self.push(header);
+
+        true
+    }
+}
+
+fn header_lines<'i, S>(input: &'i [u8], headers: &mut S, config: &ParserConfig) -> Result<(&'i [u8], BlockEnd), ParserError>
+where
+    S: HeaderSlots<'i> + ?Sized
+{
+
+    let mut input = input;
+    let mut block_bytes = 0;
+    let mut count = 0;
+
+    loop {
+
+        // https://tools.ietf.org/html/rfc7230#section-3
+        if let Ok((input, _)) = crlf_or_lf(input) {
+            return Ok((input, BlockEnd::EmptyLine));
+        }
+
+        if !input.contains(&b'\n') {
+            return Ok((input, BlockEnd::Incomplete));
+        }
+
+        let mut h = Header::new();
+
+        match header(input, &mut h) {
+            Ok((i, _)) => {
+
+                let header_bytes = input.len() - i.len();
+
+                if header_bytes > config.max_header_bytes {
+                    return Err(ParserError::HeaderTooLarge);
+                }
+
+                block_bytes += header_bytes;
+
+                if block_bytes > config.max_headers_block_bytes {
+                    return Err(ParserError::HeadersBlockTooLarge);
+                }
+
+                if !headers.store(count, h) {
+                    return Ok((input, BlockEnd::Full));
+                }
+
+                input = i;
+                count += 1;
+            },
+            Err(nom::Err::Error(_)) => return Ok((input, BlockEnd::Other)),
+            Err(nom::Err::Failure((_, nom::error::ErrorKind::Verify))) => return Err(ParserError::InvalidHeaderValue),
+            Err(nom::Err::Failure((_, nom::error::ErrorKind::Char))) => return Err(ParserError::InvalidHeaderName),
+            Err(e) => return Err(ParserError::syntax(Stage::Headers, e))
+        }
+    }
+}
+
+/// Parse HTTP Authorization header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// auth-scheme 1*SP ( token68 / #auth-param )
+///
+/// `Basic` credentials are decoded, `Bearer` and any other scheme are returned as received.
+///
+/// https://tools.ietf.org/html/rfc7235#section-4.2
+pub fn authorization(input: &[u8]) -> nom::IResult<&[u8], Credentials<'_>> {
+
+    let (input, scheme) = token(input)?;
+
+    let (input, _) = nom::bytes::complete::take_while1(|b| b == b' ')(input)?;
+
+    let (input, token) = nom::bytes::complete::take_while1(is_header_value_token)(input)?;
+
+    if scheme.eq_ignore_ascii_case(b"Basic") {
+        return match basic_credentials(token.trim_ascii()) {
+            Ok((user, pass)) => Ok((input, Credentials::Basic { user, pass })),
+            Err(_) => Err(nom::Err::Error((token, nom::error::ErrorKind::MapRes)))
+        };
+    }
+
+    if scheme.eq_ignore_ascii_case(b"Bearer") {
+        return Ok((input, Credentials::Bearer(token.trim_ascii())));
+    }
+
+    Ok((input, Credentials::Other { scheme, token }))
+}
+
+/// Parse HTTP WWW-Authenticate header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// challenge *( OWS "," OWS challenge )<br>
+/// challenge = auth-scheme [ 1*SP ( token68 / #auth-param ) ]<br>
+/// auth-param = token BWS "=" BWS ( token / quoted-string )
+///
+/// Commas separate both challenges and their parameters, a token that is not followed by `=`
+/// starts the next challenge. Quoted values are returned unescaped.
+///
+/// https://tools.ietf.org/html/rfc7235#section-2.1
+/// https://tools.ietf.org/html/rfc7235#section-4.1
+pub fn www_authenticate(input: &[u8]) -> nom::IResult<&[u8], Vec<Challenge<'_>>> {
+
+    let mut challenges = Vec::new();
+    let mut input = input;
+
+    loop {
+
+        let (rest, scheme) = match nom::sequence::preceded(list_separator, token)(input) {
+            Ok(parsed) => parsed,
+            Err(_) if !challenges.is_empty() => break,
+            Err(e) => return Err(e)
+        };
+
+        let mut params = Vec::new();
+        let mut token68 = None;
+
+        input = rest;
+
+        while let Ok((rest, param)) = nom::sequence::preceded(list_separator, auth_param)(input) {
+            params.push(param);
+            input = rest;
+        }
+
+        // A token68 takes the place of the parameters, it can end in `=` and so is only tried
+        // once no auth-param matches
+        if params.is_empty() {
+            if let Ok((rest, value)) = challenge_token68(input) {
+                token68 = Some(value);
+                input = rest;
+            }
+        }
+
+        challenges.push(Challenge { scheme, token68, params });
+    }
+
+    Ok((input, challenges))
+}
+
+/// Skips whitespace and the commas of empty list elements
+fn list_separator(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::bytes::complete::take_while(|b| is_whitespace(b) || b == b',')(input)
+}
+
+/// A token68 after the scheme, which has to end the challenge
+fn challenge_token68(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+
+    let (input, value) = nom::sequence::preceded(nom::character::complete::space1, token68)(input)?;
+
+    let (rest, _) = ows(input)?;
+
+    if !rest.is_empty() && !rest.starts_with(b",") {
+        return Err(nom::Err::Error((input, nom::error::ErrorKind::Verify)));
+    }
+
+    Ok((input, value))
+}
+
+fn auth_param(input: &[u8]) -> nom::IResult<&[u8], Param<'_>> {
+
+    let (input, name) = token(input)?;
+
+    let (input, _) = nom::sequence::tuple((bws, nom::character::complete::char('='), bws))(input)?;
+
+    let (input, value) = param_value(input)?;
+
+    Ok((input, (name, value)))
+}
+
+/// Parse HTTP quoted-string
+///
+/// # Arguments
+/// * `input` - A slice that starts with the opening quote
+///
+/// # Expected Format
+/// DQUOTE *( qdtext / quoted-pair ) DQUOTE<br>
+/// quoted-pair = "\\" ( HTAB / SP / VCHAR / obs-text )
+///
+/// Returns the content between the quotes with every quoted-pair replaced by the character it
+/// escapes, such as `a "b"` for `"a \"b\""`.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.2.6
+pub fn quoted_string(input: &[u8]) -> nom::IResult<&[u8], Vec<u8>> {
+
+    let (rest, _) = nom::character::complete::char('"')(input)?;
+
+    let mut content = Vec::with_capacity(rest.len());
+    let mut bytes = rest.iter().enumerate();
+
+    while let Some((i, &b)) = bytes.next() {
+        match b {
+            b'"' => return Ok((&rest[i + 1..], content)),
+            b'\\' => match bytes.next() {
+                Some((_, &escaped)) if is_header_value_token(escaped) => content.push(escaped),
+                _ => break
+            },
+            b if is_header_value_token(b) => content.push(b),
+            _ => break
+        }
+    }
+
+    Err(nom::Err::Error((input, nom::error::ErrorKind::Char)))
+}
+
+/// The value of the first parameter in `params` whose name matches `name`, ignoring ASCII case
+pub(crate) fn find_param<'p>(params: &'p [Param<'_>], name: &str) -> Option<&'p [u8]> {
+    params.iter().find(|(n, _)| n.eq_ignore_ascii_case(name.as_bytes())).map(|(_, v)| v.as_ref())
+}
+
+/// A parameter value, either a token or an unescaped quoted-string
+fn param_value(input: &[u8]) -> nom::IResult<&[u8], Cow<'_, [u8]>> {
+    nom::branch::alt((
+        nom::combinator::map(quoted_string, Cow::Owned),
+        nom::combinator::map(token, Cow::Borrowed),
+    ))(input)
+}
+
+/// Parse HTTP Host header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// uri-host [ ":" port ]
+///
+/// An IPv6 literal keeps its brackets, such as `[::1]`.
+///
+/// https://tools.ietf.org/html/rfc7230#section-5.4
+pub fn host(input: &[u8]) -> nom::IResult<&[u8], (&[u8], Option<u16>)> {
+
+    let (input, host) = nom::branch::alt((
+        nom::combinator::recognize(nom::sequence::delimited(
+            nom::character::complete::char('['),
+            nom::bytes::complete::is_not("]"),
+            nom::character::complete::char(']'),
+        )),
+        nom::bytes::complete::take_while1(|b| b != b':'),
+    ))(input)?;
+
+    let (input, port) = nom::combinator::opt(nom::sequence::preceded(
+        nom::character::complete::char(':'),
+        nom::combinator::map_opt(nom::character::complete::digit1, |port: &[u8]| {
+            std::str::from_utf8(port).ok()?.parse::<u16>().ok()
+        }),
+    ))(input)?;
+
+    Ok((input, (host, port)))
+}
+
+/// Parse HTTP Request Target
+///
+/// # Arguments
+/// * `method` - The request method, which decides whether the authority-form applies
+/// * `input` - A slice that holds the request target
+///
+/// # Expected Format
+/// origin-form / absolute-form / authority-form / asterisk-form<br>
+/// origin-form = absolute-path [ "?" query ]<br>
+/// absolute-form = scheme ":" hier-part [ "?" query ]<br>
+/// authority-form = uri-host [ ":" port ]<br>
+/// asterisk-form = "*"
+///
+/// https://tools.ietf.org/html/rfc7230#section-5.3
+pub fn request_target<'a>(method: &[u8], input: &'a [u8]) -> nom::IResult<&'a [u8], RequestTarget<'a>> {
+
+    let (input, target) = nom::bytes::complete::is_not(" \r\n")(input)?;
+
+    let invalid = nom::Err::Error((target, nom::error::ErrorKind::Verify));
+
+    // The authority-form is only distinguishable from an absolute URI by the method
+    if method == b"CONNECT" {
+        return match host(target) {
+            Ok(([], (host, port))) => Ok((input, RequestTarget::Authority { host, port })),
+            _ => Err(invalid)
+        };
+    }
+
+    if target == b"*" {
+        return Ok((input, RequestTarget::Asterisk));
+    }
+
+    if target[0] == b'/' {
+
+        let (path, query) = split_query(target);
+
+        return Ok((input, RequestTarget::Origin { path, query }));
+    }
+
+    // https://tools.ietf.org/html/rfc3986#section-3.1
+    let (scheme, hier_part) = match target.iter().position(|&b| b == b':') {
+        Some(i) if i > 0 => (&target[..i], &target[i + 1..]),
+        _ => return Err(invalid)
+    };
+
+    // scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+    if !scheme[0].is_ascii_alphabetic() || !scheme.iter().all(|&b| b.is_ascii_alphanumeric() || b"+-.".contains(&b)) {
+        return Err(invalid);
+    }
+
+    let (hier_part, query) = split_query(hier_part);
+
+    let (authority, path) = match hier_part {
+        [b'/', b'/', rest @ ..] => match rest.iter().position(|&b| b == b'/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, &b""[..])
+        },
+        _ => (&b""[..], hier_part)
+    };
+
+    Ok((input, RequestTarget::Absolute { scheme, authority, path, query }))
+}
+
+// Splits a target at its first `?` into the part before it and the query after it
+fn split_query(input: &[u8]) -> (&[u8], Option<&[u8]>) {
+    match input.iter().position(|&b| b == b'?') {
+        Some(i) => (&input[..i], Some(&input[i + 1..])),
+        None => (input, None)
+    }
+}
+
+/// Parse HTTP Forwarded header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// forwarded-element *( OWS "," OWS forwarded-element )<br>
+/// forwarded-element = forwarded-pair *( ";" forwarded-pair )<br>
+/// forwarded-pair = token "=" value
+///
+/// Returns each element as its `(parameter, value)` pairs, such as `for`, `by`, `host` and
+/// `proto`, with the surrounding quotes removed from quoted values.
+///
+/// https://tools.ietf.org/html/rfc7239#section-4
+pub fn forwarded(input: &[u8]) -> Vec<Vec<(&[u8], &[u8])>> {
+    input
+        .split(|&b| b == b',')
+        .map(|element| {
+            element
+                .split(|&b| b == b';')
+                .filter_map(|pair| {
+
+                    let pair = pair.trim_ascii();
+                    let i = pair.iter().position(|&b| b == b'=')?;
+                    let value = &pair[i + 1..];
+
+                    let value = match value {
+                        [b'"', inner @ .., b'"'] => inner,
+                        _ => value
+                    };
+
+                    Some((&pair[..i], value))
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|element| !element.is_empty())
+        .collect()
+}
+
+/// Parse HTTP Cache-Control header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// cache-directive *( OWS "," OWS cache-directive )<br>
+/// cache-directive = token [ "=" ( token / quoted-string ) ]
+///
+/// Quoted arguments are split on commas like the rest of the list, which only matters for the
+/// field names following `no-cache` and `private`, and those are not kept.
+///
+/// https://tools.ietf.org/html/rfc7234#section-5.2
+pub fn cache_control(input: &[u8]) -> Vec<CacheDirective<'_>> {
+    comma_list(input)
+        .into_iter()
+        .map(|directive| {
+
+            let (name, argument) = match directive.iter().position(|&b| b == b'=') {
+                Some(i) => (directive[..i].trim_ascii(), Some(directive[i + 1..].trim_ascii())),
+                None => (directive, None)
+            };
+
+            let argument = match argument {
+                Some([b'"', inner @ .., b'"']) => Some(inner),
+                _ => argument
+            };
+
+            let seconds = argument.and_then(|argument| std::str::from_utf8(argument).ok()?.parse::<u64>().ok());
+
+            let is = |other: &[u8]| name.eq_ignore_ascii_case(other);
+
+            match (argument, seconds) {
+                (Some(_), Some(seconds)) if is(b"max-age") => CacheDirective::MaxAge(seconds),
+                (Some(_), Some(seconds)) if is(b"max-stale") => CacheDirective::MaxStale(Some(seconds)),
+                (Some(_), Some(seconds)) if is(b"min-fresh") => CacheDirective::MinFresh(seconds),
+                (Some(_), Some(seconds)) if is(b"s-maxage") => CacheDirective::SMaxAge(seconds),
+                (None, _) if is(b"max-stale") => CacheDirective::MaxStale(None),
+                (None, _) if is(b"no-store") => CacheDirective::NoStore,
+                (None, _) if is(b"no-transform") => CacheDirective::NoTransform,
+                (None, _) if is(b"only-if-cached") => CacheDirective::OnlyIfCached,
+                (None, _) if is(b"must-revalidate") => CacheDirective::MustRevalidate,
+                (None, _) if is(b"proxy-revalidate") => CacheDirective::ProxyRevalidate,
+                (None, _) if is(b"public") => CacheDirective::Public,
+                _ if is(b"no-cache") => CacheDirective::NoCache,
+                _ if is(b"private") => CacheDirective::Private,
+                _ => CacheDirective::Extension(name, argument)
+            }
+        })
+        .collect()
+}
+
+/// Parse HTTP ETag header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// [ "W/" ] DQUOTE *etagc DQUOTE<br>
+/// etagc = %x21 / %x23-7E / obs-text
+///
+/// https://tools.ietf.org/html/rfc7232#section-2.3
+pub fn etag(input: &[u8]) -> nom::IResult<&[u8], ETag<'_>> {
+
+    let (input, weak) = nom::combinator::opt(nom::bytes::complete::tag("W/"))(input)?;
+
+    let (input, tag) = nom::sequence::delimited(
+        nom::character::complete::char('"'),
+        nom::bytes::complete::take_while(|b| b == 0x21 || (0x23..=0x7E).contains(&b) || b >= 0x80),
+        nom::character::complete::char('"'),
+    )(input)?;
+
+    Ok((input, ETag { weak: weak.is_some(), tag }))
+}
+
+/// Parse HTTP If-None-Match header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// "*" / entity-tag *( OWS "," OWS entity-tag )
+///
+/// https://tools.ietf.org/html/rfc7232#section-3.2
+pub fn if_none_match(input: &[u8]) -> nom::IResult<&[u8], IfNoneMatch<'_>> {
+    nom::branch::alt((
+        nom::combinator::map(nom::character::complete::char('*'), |_| IfNoneMatch::Any),
+        nom::combinator::map(
+            nom::multi::separated_nonempty_list(nom::sequence::tuple((ows, nom::character::complete::char(','), ows)), etag),
+            IfNoneMatch::Tags
+        ),
+    ))(input)
+}
+
+/// Parse HTTP list header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// #element = [ ( "," / element ) *( OWS "," [ OWS element ] ) ]
+///
+/// Returns each element with the whitespace around it trimmed, in order. Empty elements, as
+/// in `a, , b,`, are allowed by the `#` rule and dropped. Commas inside quoted-strings are not
+/// told apart, so this suits lists of tokens such as `Connection` or `Upgrade`.
+///
+/// https://tools.ietf.org/html/rfc7230#section-7
+pub fn comma_list(input: &[u8]) -> Vec<&[u8]> {
+    input
+        .split(|&b| b == b',')
+        .map(|element| element.trim_ascii())
+        .filter(|element| !element.is_empty())
+        .collect()
+}
+
+/// Parse HTTP Connection header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// connection-option *( OWS "," OWS connection-option )<br>
+/// connection-option = token
+///
+/// Tokens are returned as received, they are case-insensitive so compare them with
+/// `eq_ignore_ascii_case`.
+///
+/// https://tools.ietf.org/html/rfc7230#section-6.1
+pub fn connection_tokens(input: &[u8]) -> Vec<&[u8]> {
+    comma_list(input)
+}
+
+/// Parse HTTP Accept-Encoding header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// codings *( OWS "," OWS codings )<br>
+/// codings = ( content-coding / "identity" / "*" ) [ OWS ";" OWS "q=" qvalue ]
+///
+/// Returns each coding with its quality, `1.0` when omitted, ordered from most to least
+/// preferred. Codings with a quality of `0` are kept, they mark the coding as not acceptable,
+/// codings with a malformed quality are skipped.
+///
+/// https://tools.ietf.org/html/rfc7231#section-5.3.4
+pub fn accept_encoding(input: &[u8]) -> Vec<(&[u8], f32)> {
+    weighted_list(input)
+}
 
-/// Parses [RFC7230] compliant HTTP Messages<br>
-/// https://tools.ietf.org/html/rfc7230
+/// Parse HTTP Accept-Language header value
 ///
-/// # Reference
-/// * `OCTET`   - any 8-bit sequence of data<br>
-/// * `CHAR`    - any US-ASCII character (octets 0 - 127)<br>
-/// * `UPALPHA` - any US-ASCII uppercase letter "A".."Z"<br>
-/// * `LOALPHA` - any US-ASCII lowercase letter "a".."z"<br>
-/// * `ALPHA`   - UPALPHA | LOALPHA<br>
-/// * `DIGIT`   - any US-ASCII digit "0".."9"<br>
-/// * `CTL`     - any US-ASCII control character (octets 0 - 31) and DEL (127)<br>
-/// * `CR`      - US-ASCII CR, carriage return (13)<br>
-/// * `LF`      - US-ASCII LF, linefeed (10)<br>
-/// * `SP`      - US-ASCII SP, space (32)<br>
-/// * `HT`      - US-ASCII HT, horizontal-tab (9)<br>
-/// * `"`       - US-ASCII double-quote mark (34)<br>
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// language-range [ OWS ";" OWS "q=" qvalue ] *( OWS "," OWS language-range [ OWS ";" OWS "q=" qvalue ] )
+///
+/// Returns each language range with its quality, `1.0` when omitted, ordered from most to
+/// least preferred.
+///
+/// https://tools.ietf.org/html/rfc7231#section-5.3.5
+pub fn accept_language(input: &[u8]) -> Vec<(&[u8], f32)> {
+    weighted_list(input)
+}
 
-static HEADER_NAME_MAP: [bool; 256] = byte_map![
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 1, 0, 1, 1, 1, 1, 1, 0, 0, 1, 1, 0, 1, 1, 0,
-    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0,
-    0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
-    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 1, 1,
-    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
-    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 1, 0, 1, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-];
+// Splits a comma separated list of `value;q=qvalue` elements, ordering them by descending
+// quality while keeping the original order between equal qualities
+// https://tools.ietf.org/html/rfc7231#section-5.3.1
+fn weighted_list(input: &[u8]) -> Vec<(&[u8], f32)> {
 
-/// Parse HTTP Request Line
+    let mut list = comma_list(input)
+        .into_iter()
+        .filter_map(|element| {
+
+            let mut params = element.split(|&b| b == b';');
+            let value = params.next()?.trim_ascii();
+
+            if value.is_empty() {
+                return None;
+            }
+
+            let quality = match params.map(|p| p.trim_ascii()).find(|p| p.len() > 1 && p[..2].eq_ignore_ascii_case(b"q=")) {
+                Some(q) => std::str::from_utf8(&q[2..]).ok()?.parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?,
+                None => 1.0
+            };
+
+            Some((value, quality))
+        })
+        .collect::<Vec<_>>();
+
+    list.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    list
+}
+
+/// Parse URI query
 ///
 /// # Arguments
-/// * `input` - A slice that holds the http message
+/// * `input` - A slice that holds the query, without the leading `?`
 ///
 /// # Expected Format
-/// Method SP request-target/path SP HTTP-Version CRLF
+/// pair *( "&" pair )<br>
+/// pair = name [ "=" value ]
 ///
-/// https://tools.ietf.org/html/rfc7230#section-3.1.1
-pub fn request_line(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8], &[u8], &[u8])> {
-    nom::sequence::tuple((method, path, version, nom::character::complete::crlf))(input)
+/// Returns each `(name, value)` pair as received, a pair without `=` has an empty value.
+///
+/// https://tools.ietf.org/html/rfc3986#section-3.4
+pub fn query_pairs(input: &[u8]) -> Vec<(&[u8], &[u8])> {
+    input
+        .split(|&b| b == b'&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.iter().position(|&b| b == b'=') {
+            Some(i) => (&pair[..i], &pair[i + 1..]),
+            None => (pair, &b""[..])
+        })
+        .collect()
 }
 
-/// Parse HTTP Header
+/// Decode percent-encoded octets
 ///
 /// # Arguments
-/// * `input` - A slice that holds the http message
-/// * `header` - A mutable instance of the Header struct
+/// * `input` - A slice that holds the encoded bytes
 ///
 /// # Expected Format
-/// Header-Name: OWS Header Value OWS CRLF
+/// *( unreserved / pct-encoded / sub-delims / ":" / "@" / "/" / "?" )<br>
+/// pct-encoded = "%" HEXDIG HEXDIG
 ///
-/// https://tools.ietf.org/html/rfc7231#section-4
-pub fn header<'i, 'h>(input: &'i [u8], header: &'h mut Header<'i>) -> nom::IResult<&'i [u8], ()> {
+/// https://tools.ietf.org/html/rfc3986#section-2.1
+pub fn percent_decode(input: &[u8]) -> Result<Vec<u8>, ParserError> {
 
-    let (input, name) = nom::bytes::complete::take_while(is_header_name_token)(input)?;
+    let mut decoded = Vec::with_capacity(input.len());
+    let mut bytes = input.iter();
 
-    header.name = name;
+    while let Some(&b) = bytes.next() {
 
-    let (input, _) = nom::character::complete::char(':')(input)?;
+        if b != b'%' {
+            decoded.push(b);
+            continue;
+        }
 
-    let (input, value) = nom::sequence::delimited(
-        nom::bytes::complete::tag(" "),
-        nom::bytes::complete::is_not("\r\n"),
-        nom::bytes::complete::tag("\r\n"),
-    )(input)?;
+        let mut hex_digit = || bytes.next().and_then(|&b| (b as char).to_digit(16));
 
-    header.value = value;
+        match (hex_digit(), hex_digit()) {
+            (Some(high), Some(low)) => decoded.push((high * 16 + low) as u8),
+            _ => return Err(ParserError::InvalidPercentEncoding)
+        }
+    }
 
-    Ok((input, ()))
+    Ok(decoded)
 }
 
-/// Parse HTTP Body
+/// Parse HTTP Content-Range header value
 ///
 /// # Arguments
-/// * `length` - Size of input to parse
-/// * `input` - A slice that holds the http message
+/// * `input` - A slice that holds the header value
 ///
 /// # Expected Format
-/// CRLF *OCTET
+/// "bytes" SP ( byte-range "/" ( complete-length / "*" ) / "*/" complete-length )<br>
+/// byte-range = first-byte-pos "-" last-byte-pos
 ///
-/// https://tools.ietf.org/html/rfc7230#section-3.3
-pub fn body(length: usize, input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+/// https://tools.ietf.org/html/rfc7233#section-4.2
+pub fn content_range(input: &[u8]) -> nom::IResult<&[u8], ContentRange> {
 
-    let (input, _) = nom::character::complete::crlf(input)?;
+    let (input, _) = nom::bytes::complete::tag("bytes ")(input)?;
 
-    nom::bytes::complete::take(length)(input)
+    let (input, range) = nom::branch::alt((
+        nom::combinator::map(nom::character::complete::char('*'), |_| None),
+        nom::combinator::map(
+            nom::sequence::separated_pair(range_digits, nom::character::complete::char('-'), range_digits),
+            Some
+        ),
+    ))(input)?;
+
+    let (input, _) = nom::character::complete::char('/')(input)?;
+
+    let (input, total) = match range {
+        Some(_) => nom::branch::alt((
+            nom::combinator::map(nom::character::complete::char('*'), |_| None),
+            nom::combinator::map(range_digits, Some),
+        ))(input)?,
+        None => nom::combinator::map(range_digits, Some)(input)?
+    };
+
+    // The range must be ordered and fall within the complete length
+    let valid = match (range, total) {
+        (Some((first, last)), Some(total)) => first <= last && last < total,
+        (Some((first, last)), None) => first <= last,
+        (None, _) => true
+    };
+
+    if !valid {
+        return Err(nom::Err::Error((input, nom::error::ErrorKind::Verify)));
+    }
+
+    Ok((input, ContentRange { range, total }))
+}
+
+/// Parse HTTP Content-Type header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// type "/" subtype *( OWS ";" OWS parameter )<br>
+/// parameter = token "=" ( token / quoted-string )
+///
+/// Types, subtypes and parameter names are case-insensitive and are returned as received.
+///
+/// https://tools.ietf.org/html/rfc7231#section-3.1.1.1
+pub fn content_type(input: &[u8]) -> nom::IResult<&[u8], ContentType<'_>> {
+
+    let (input, (media_type, subtype)) = nom::sequence::separated_pair(token, nom::character::complete::char('/'), token)(input)?;
+
+    let (input, params) = nom::multi::many0(nom::sequence::preceded(
+        nom::sequence::tuple((ows, nom::character::complete::char(';'), ows)),
+        nom::sequence::separated_pair(token, nom::character::complete::char('='), param_value),
+    ))(input)?;
+
+    Ok((input, ContentType { media_type, subtype, params }))
 }
 
-pub fn headers_iterator<'i, 'h>(input: &'i [u8], headers: &'h mut [Header<'i>]) -> nom::IResult<&'i [u8], ()> {
+/// Parse HTTP Content-Disposition header value
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// disposition-type *( OWS ";" OWS disposition-parm )<br>
+/// disposition-parm = token "=" ( token / quoted-string )
+///
+/// The extended `filename*` parameter is returned still encoded, `ContentDisposition::filename`
+/// decodes it.
+///
+/// https://tools.ietf.org/html/rfc6266#section-4.1
+pub fn content_disposition(input: &[u8]) -> nom::IResult<&[u8], ContentDisposition<'_>> {
 
-    let mut iter = headers.iter_mut();
-    let mut input = input;
+    let (input, disposition_type) = token(input)?;
 
-    loop {
+    let (input, params) = nom::multi::many0(nom::sequence::preceded(
+        nom::sequence::tuple((ows, nom::character::complete::char(';'), ows)),
+        nom::sequence::separated_pair(token, nom::character::complete::char('='), param_value),
+    ))(input)?;
 
-        let h = match iter.next() {
-            Some(header) => header,
-            None => break
-        };
+    Ok((input, ContentDisposition { disposition_type, params }))
+}
+
+fn range_digits(input: &[u8]) -> nom::IResult<&[u8], u64> {
+    nom::combinator::map_opt(nom::character::complete::digit1, |digits: &[u8]| {
+        std::str::from_utf8(digits).ok()?.parse::<u64>().ok()
+    })(input)
+}
 
-        match header(input, h) {
-            Ok((i, _)) => input = i,
-            Err(nom::Err::Error(_)) => break,
-            e => return e
+/// Parse HTTP-date
+///
+/// # Arguments
+/// * `input` - A slice that holds the header value
+///
+/// # Expected Format
+/// Any of the following:
+/// * `IMF-fixdate` - Sun, 06 Nov 1994 08:49:37 GMT<br>
+/// * `rfc850-date` - Sunday, 06-Nov-94 08:49:37 GMT<br>
+/// * `asctime-date` - Sun Nov  6 08:49:37 1994<br>
+///
+/// https://tools.ietf.org/html/rfc7231#section-7.1.1.1
+pub fn http_date(input: &[u8]) -> nom::IResult<&[u8], HttpDate> {
+    nom::combinator::verify(
+        nom::branch::alt((imf_fixdate, rfc850_date, asctime_date)),
+        |date: &HttpDate| {
+            date.day >= 1 && date.day <= 31 && date.hour <= 23 && date.minute <= 59 && date.second <= 60
         }
+    )(input)
+}
+
+// day-name "," SP day SP month SP year SP time-of-day SP GMT
+fn imf_fixdate(input: &[u8]) -> nom::IResult<&[u8], HttpDate> {
+
+    let (input, _) = nom::sequence::terminated(nom::character::complete::alpha1, nom::bytes::complete::tag(", "))(input)?;
+
+    let (input, day) = nom::sequence::terminated(date_digits(2), nom::bytes::complete::tag(" "))(input)?;
+
+    let (input, month) = nom::sequence::terminated(month, nom::bytes::complete::tag(" "))(input)?;
+
+    let (input, year) = nom::sequence::terminated(date_digits(4), nom::bytes::complete::tag(" "))(input)?;
+
+    let (input, (hour, minute, second)) = nom::sequence::terminated(time_of_day, nom::bytes::complete::tag(" GMT"))(input)?;
+
+    Ok((input, HttpDate { year, month, day: day as u8, hour, minute, second }))
+}
+
+// day-name-l "," SP day "-" month "-" 2DIGIT SP time-of-day SP GMT
+fn rfc850_date(input: &[u8]) -> nom::IResult<&[u8], HttpDate> {
+
+    let (input, _) = nom::sequence::terminated(nom::character::complete::alpha1, nom::bytes::complete::tag(", "))(input)?;
+
+    let (input, day) = nom::sequence::terminated(date_digits(2), nom::bytes::complete::tag("-"))(input)?;
+
+    let (input, month) = nom::sequence::terminated(month, nom::bytes::complete::tag("-"))(input)?;
+
+    let (input, year) = nom::sequence::terminated(date_digits(2), nom::bytes::complete::tag(" "))(input)?;
+
+    let (input, (hour, minute, second)) = nom::sequence::terminated(time_of_day, nom::bytes::complete::tag(" GMT"))(input)?;
+
+    // Two digit years are resolved into the most recent matching century, without a reference
+    // clock years before 70 are taken to be in the 2000s
+    let year = if year < 70 { 2000 + year } else { 1900 + year };
+
+    Ok((input, HttpDate { year, month, day: day as u8, hour, minute, second }))
+}
+
+// day-name SP month SP ( 2DIGIT / ( SP DIGIT ) ) SP time-of-day SP year
+fn asctime_date(input: &[u8]) -> nom::IResult<&[u8], HttpDate> {
+
+    let (input, _) = nom::sequence::terminated(nom::character::complete::alpha1, nom::bytes::complete::tag(" "))(input)?;
+
+    let (input, month) = nom::sequence::terminated(month, nom::bytes::complete::tag(" "))(input)?;
+
+    let (input, day) = nom::sequence::terminated(
+        nom::branch::alt((date_digits(2), nom::sequence::preceded(nom::bytes::complete::tag(" "), date_digits(1)))),
+        nom::bytes::complete::tag(" ")
+    )(input)?;
 
+    let (input, (hour, minute, second)) = nom::sequence::terminated(time_of_day, nom::bytes::complete::tag(" "))(input)?;
+
+    let (input, year) = date_digits(4)(input)?;
+
+    Ok((input, HttpDate { year, month, day: day as u8, hour, minute, second }))
+}
+
+// hour ":" minute ":" second
+fn time_of_day(input: &[u8]) -> nom::IResult<&[u8], (u8, u8, u8)> {
+
+    let (input, hour) = nom::sequence::terminated(date_digits(2), nom::bytes::complete::tag(":"))(input)?;
+
+    let (input, minute) = nom::sequence::terminated(date_digits(2), nom::bytes::complete::tag(":"))(input)?;
+
+    let (input, second) = date_digits(2)(input)?;
+
+    Ok((input, (hour as u8, minute as u8, second as u8)))
+}
+
+// Case-sensitive three letter month name, returned as 1 through 12
+fn month(input: &[u8]) -> nom::IResult<&[u8], u8> {
+
+    const MONTHS: [&[u8]; 12] = [
+        b"Jan", b"Feb", b"Mar", b"Apr", b"May", b"Jun", b"Jul", b"Aug", b"Sep", b"Oct", b"Nov", b"Dec"
+    ];
+
+    nom::combinator::map_opt(nom::bytes::complete::take(3usize), |name: &[u8]| {
+        MONTHS.iter().position(|&m| m == name).map(|i| i as u8 + 1)
+    })(input)
+}
+
+// Exactly `count` digits as a number
+fn date_digits(count: usize) -> impl Fn(&[u8]) -> nom::IResult<&[u8], u16> {
+    move |input: &[u8]| {
+        nom::combinator::map(
+            nom::bytes::complete::take_while_m_n(count, count, nom::character::is_digit),
+            |digits: &[u8]| digits.iter().fold(0, |n, &d| n * 10 + u16::from(d - b'0'))
+        )(input)
     }
+}
 
-    Ok((input, ()))
+/// Decode HTTP Basic credentials
+///
+/// # Arguments
+/// * `token` - The base64 encoded `user-id:password` pair
+///
+/// https://tools.ietf.org/html/rfc7617#section-2
+pub fn basic_credentials(token: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ParserError> {
+
+    let decoded = match base64_decode(token) {
+        Some(decoded) => decoded,
+        None => return Err(ParserError::InvalidAuthorization)
+    };
+
+    match decoded.iter().position(|&b| b == b':') {
+        Some(i) => Ok((decoded[..i].to_vec(), decoded[i + 1..].to_vec())),
+        None => Err(ParserError::InvalidAuthorization)
+    }
+}
+
+// Standard base64 alphabet with optional padding
+// https://tools.ietf.org/html/rfc4648#section-4
+fn base64_decode(input: &[u8]) -> Option<Vec<u8>> {
+
+    let padding = input.iter().rev().take_while(|&&b| b == b'=').count();
+
+    if padding > 2 || input.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &b in &input[..input.len() - padding] {
+
+        let value = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None
+        };
+
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(output)
+}
+
+/// Parse HTTP token
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// 1*tchar, where tchar is any VCHAR except delimiters: `"(),/:;<=>?@[\]{}`
+///
+/// Used for methods, header names, transfer codings and most list elements.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3.2.6
+pub fn token(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::bytes::complete::take_while1(is_token)(input)
+}
+
+/// Parse HTTP token68
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+///
+/// Used for credentials in a base64 like encoding, such as those of `Negotiate`.
+///
+/// https://tools.ietf.org/html/rfc7235#section-2.1
+pub fn token68(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::combinator::recognize(nom::sequence::pair(
+        nom::bytes::complete::take_while1(|b: u8| b.is_ascii_alphanumeric() || b"-._~+/".contains(&b)),
+        nom::bytes::complete::take_while(|b| b == b'='),
+    ))(input)
+}
+
+fn is_token(b: u8) -> bool {
+    TOKEN_MAP[b as usize]
 }
 
-fn is_header_name_token(b: u8) -> bool {
-    HEADER_NAME_MAP[b as usize]
+fn is_header_value_token(b: u8) -> bool {
+    HEADER_VALUE_MAP[b as usize]
 }
 
 /// Parse HTTP request method
@@ -133,20 +1429,19 @@ fn is_header_name_token(b: u8) -> bool {
 /// * `input` - A slice that holds the http message
 ///
 /// # Expected Format
-/// Any of the following: GET, HEAD, POST, PUT, DELETE, CONNECT, OPTIONS, TRACE, PATCH
+/// A token, such as GET, HEAD, POST, PUT, DELETE, CONNECT, OPTIONS, TRACE, PATCH or an
+/// extension method like PROPFIND
 ///
 /// https://tools.ietf.org/html/rfc7231#section-4
 pub fn method(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
 
-    // @TODO: validate?
-
     // Discard CRLF if found
     let (input, _) = nom::combinator::opt(nom::character::complete::crlf)(input)?;
 
     // Discard numbers if found
     let (input, _) = nom::combinator::opt(nom::character::complete::digit0)(input)?;
 
-    nom::bytes::complete::take_while_m_n(3, 7, nom::character::is_alphabetic)(input)
+    token(input)
 }
 
 /// Parse HTTP request target
@@ -182,8 +1477,21 @@ pub fn version(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
     )(input)
 }
 
+/// Validate an HTTP version as matched by `version`
+///
+/// # Arguments
+/// * `input` - A slice that holds the version, such as `1.1`
+///
+/// # Expected Format
+/// DIGIT "." DIGIT / "2" / "3"
+///
+/// https://tools.ietf.org/html/rfc7230#section-2.6
+pub fn validate_version(input: &[u8]) -> Result<(), ParserError> {
+    Version::from_bytes(input).map(|_| ())
+}
+
 fn is_version(input: u8) -> bool {
-    input >= b'0' && input <= b'9' || input == b'.'
+    input.is_ascii_digit() || input == b'.'
 }
 
 // US-ASCII SP, space (32) delimited
@@ -195,33 +1503,192 @@ pub fn whitespace_delimited(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
     )(input)
 }
 
+// Optional whitespace, OWS = *( SP / HTAB )
+// https://tools.ietf.org/html/rfc7230#section-3.2.3
+pub fn ows(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::bytes::complete::take_while(is_whitespace)(input)
+}
+
+// Required whitespace, RWS = 1*( SP / HTAB )
+pub fn rws(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::bytes::complete::take_while1(is_whitespace)(input)
+}
+
+// "Bad" whitespace, BWS = OWS, allowed for historical reasons but never produced
+pub fn bws(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    ows(input)
+}
+
+fn is_whitespace(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+// US-ASCII CR, carriage return (13) + US-ASCII LF, linefeed (10)
+pub fn crlf(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::character::complete::crlf(input)
+}
+
+// US-ASCII CR, carriage return (13) + US-ASCII LF, linefeed (10), or a bare LF
+// https://tools.ietf.org/html/rfc7230#section-3.5
+pub fn crlf_or_lf(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+    nom::branch::alt((nom::bytes::complete::tag("\r\n"), nom::bytes::complete::tag("\n")))(input)
+}
+
 // Not US-ASCII CR, carriage return (13) + US-ASCII LF, linefeed (10)
 pub fn not_crlf(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
     nom::bytes::complete::is_not("\r\n")(input)
 }
 
-#[derive(Debug, PartialEq)]
+/// Parse HTTP Message, request or response
+///
+/// # Arguments
+/// * `input` - A slice that holds the http message
+///
+/// # Expected Format
+/// start-line *( header-field CRLF ) CRLF [ message-body ]<br>
+/// start-line = request-line / status-line
+///
+/// A message starting with `HTTP/` is parsed as a response, any other as a request. Request
+/// headers are stored in a `Vec`, so there is no limit on their number.
+///
+/// https://tools.ietf.org/html/rfc7230#section-3
+pub fn message(input: &[u8]) -> Result<Message<'_>, ParserError> {
+
+    // A method is a token, which never holds the "/" of "HTTP/"
+    if input.starts_with(b"HTTP/") {
+
+        let mut response = Response::new();
+
+        response.parse(input)?;
+
+        return Ok(Message::Response(response));
+    }
+
+    let mut request = Request::with_vec(Vec::new());
+
+    request.parse(input)?;
+
+    Ok(Message::Request(request))
+}
+
+/// The progress of parsing a message from a buffer that may not hold all of it yet
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Status {
+
+    /// The message is complete and spans this many bytes of the input.
+    Complete(usize),
+
+    /// The input ends before the message does.
+    Partial
+}
+
+/// The part of a message in which a syntax error was found
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Stage {
+
+    /// The request line, such as `GET / HTTP/1.1`.
+    RequestLine,
+
+    /// The status line, such as `HTTP/1.1 200 OK`.
+    StatusLine,
+
+    /// A header line, such as `Host: subdomain.domain.tld`.
+    Headers
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ParserError {
 
     /// Represents a failure when reading HTTP Message request line.
     RequestLine,
 
+    /// Represents a method longer than the configured limit.
+    MethodTooLong,
+
+    /// Represents a request target longer than the configured limit.
+    RequestTargetTooLong,
+
+    /// Represents an HTTP version that is not one of the recognized forms, such as `1.2.3`.
+    InvalidVersion,
+
     /// Represents a failure when reading HTTP Message headers.
     Headers,
 
+    /// Represents more headers than the header storage can hold.
+    TooManyHeaders,
+
+    /// Represents a header value holding a byte that is not allowed in field-content.
+    InvalidHeaderValue,
+
+    /// Represents a header name holding a byte that is not allowed in a token, such as the
+    /// space in `Bad Header: x`.
+    InvalidHeaderName,
+
+    /// Represents a single header longer than the configured limit.
+    HeaderTooLarge,
+
+    /// Represents a header block longer than the configured limit.
+    HeadersBlockTooLarge,
+
     /// Represents a failure when reading HTTP Message body.
     Body,
 
+    /// Represents a body longer than the configured limit.
+    BodyTooLarge,
+
+    /// Represents a body cut short of its declared `Content-Length`, with the number of bytes
+    /// declared and received, so that more input can be read.
+    IncompleteBody { expected: usize, got: usize },
+
+    /// Represents a chunk size that does not fit in a `usize`.
+    ChunkSizeOverflow,
+
     /// Represents a failure when reading HTTP Message Content Length Header
     ContentLength,
 
+    /// Represents a message framed by both Content-Length and Transfer-Encoding headers.
+    ConflictingFraming,
+
+    /// Represents several Content-Length headers holding different values.
+    ConflictingContentLength,
+
+    /// Represents a request whose final transfer coding is not chunked.
+    TransferEncoding,
+
+    /// Represents a transfer coding that cannot be decoded, such as `br`.
+    UnsupportedTransferEncoding,
+
     /// Represents a failure when reading HTTP Message headers.
     InvalidUtf8Content(std::str::Utf8Error),
 
+    /// Represents a failure when decoding a compressed HTTP Message body.
+    Decompression,
+
+    /// Represents malformed credentials in an HTTP Message Authorization header.
+    InvalidAuthorization,
+
+    /// Represents a `%` that is not followed by two hexadecimal digits.
+    InvalidPercentEncoding,
+
+    /// Represents input rejected by one of the nom parsers, with the kind of parser that
+    /// rejected it, such as `Tag` for a missing `HTTP/`.
+    Syntax { stage: Stage, kind: nom::error::ErrorKind },
+
     /// Represents an unknown failure.
     Unknown
 }
 
+impl ParserError {
+
+    /// Wraps a nom failure found while parsing `stage`.
+    pub(crate) fn syntax(stage: Stage, e: nom::Err<(&[u8], nom::error::ErrorKind)>) -> ParserError {
+        match e {
+            nom::Err::Error((_, kind)) | nom::Err::Failure((_, kind)) => ParserError::Syntax { stage, kind },
+            nom::Err::Incomplete(_) => ParserError::Syntax { stage, kind: nom::error::ErrorKind::Complete }
+        }
+    }
+}
+
 // Allow ParserError to be treated like any other error
 impl Error for ParserError {}
 
@@ -230,10 +1697,31 @@ impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ParserError::RequestLine => write!(f, "ParserError: Unable to parse HTTP Message request line."),
+            ParserError::MethodTooLong => write!(f, "ParserError: HTTP Message method is too long."),
+            ParserError::RequestTargetTooLong => write!(f, "ParserError: HTTP Message request target is too long."),
+            ParserError::InvalidVersion => write!(f, "ParserError: Invalid HTTP Message protocol version."),
             ParserError::Headers => write!(f, "ParserError: Unable to parse HTTP Message headers."),
+            ParserError::TooManyHeaders => write!(f, "ParserError: HTTP Message has too many headers."),
+            ParserError::InvalidHeaderValue => write!(f, "ParserError: Invalid character in HTTP Message header value."),
+            ParserError::InvalidHeaderName => write!(f, "ParserError: Invalid character in HTTP Message header name."),
+            ParserError::HeaderTooLarge => write!(f, "ParserError: HTTP Message header is too large."),
+            ParserError::HeadersBlockTooLarge => write!(f, "ParserError: HTTP Message headers are too large."),
             ParserError::Body => write!(f, "ParserError: Unable to parse HTTP Message body."),
+            ParserError::BodyTooLarge => write!(f, "ParserError: HTTP Message body is too large."),
+            ParserError::IncompleteBody { expected, got } => write!(f, "ParserError: HTTP Message body has {} of {} bytes.", got, expected),
+            ParserError::ChunkSizeOverflow => write!(f, "ParserError: HTTP Message chunk size is too large."),
             ParserError::ContentLength => write!(f, "ParserError: Unable to parse HTTP Message Content-Length header."),
+            ParserError::ConflictingFraming => write!(f, "ParserError: HTTP Message has both Content-Length and Transfer-Encoding headers."),
+            ParserError::ConflictingContentLength => write!(f, "ParserError: HTTP Message has conflicting Content-Length headers."),
+            ParserError::TransferEncoding => write!(f, "ParserError: HTTP Message Transfer-Encoding does not end with chunked."),
+            ParserError::UnsupportedTransferEncoding => write!(f, "ParserError: Unsupported HTTP Message transfer coding."),
             ParserError::InvalidUtf8Content(ref e) => write!(f, "ParserError: {}", e),
+            ParserError::Decompression => write!(f, "ParserError: Unable to decode HTTP Message body."),
+            ParserError::InvalidAuthorization => write!(f, "ParserError: Unable to parse HTTP Message Authorization header."),
+            ParserError::InvalidPercentEncoding => write!(f, "ParserError: Invalid percent-encoding in HTTP Message request target."),
+            ParserError::Syntax { stage: Stage::RequestLine, kind } => write!(f, "ParserError: Invalid HTTP Message request line syntax ({:?}).", kind),
+            ParserError::Syntax { stage: Stage::StatusLine, kind } => write!(f, "ParserError: Invalid HTTP Message status line syntax ({:?}).", kind),
+            ParserError::Syntax { stage: Stage::Headers, kind } => write!(f, "ParserError: Invalid HTTP Message header syntax ({:?}).", kind),
             ParserError::Unknown => write!(f, "ParserError: An unknown error occurred.")
         }
     }
@@ -0,0 +1,244 @@
+use crate::http::parse::Status;
+use crate::http::body::BodyLength;
+use crate::http::request::Request;
+use crate::http::parse::crlf_or_lf;
+use crate::http::parse::ParserError;
+use crate::http::body::ChunkedDecoder;
+use crate::http::config::ParserConfig;
+use crate::http::parse::request_line_partial;
+
+/// The part of the message a `Parser` is waiting on
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ParseState {
+
+    /// The request line has not been completed yet.
+    RequestLine,
+
+    /// The empty line that ends the headers has not been reached yet.
+    Headers,
+
+    /// The body, as framed by the headers, has not been completed yet.
+    Body,
+
+    /// The whole message has been received.
+    Complete
+}
+
+/// Parses a request fed to it in pieces, as they are read from a connection
+///
+/// Fed bytes are buffered, and each call to `feed` carries on from the last completed line or
+/// chunk rather than parsing the message from the start.
+#[derive(Debug)]
+pub struct Parser {
+
+    /// Every byte fed so far.
+    buffer: Vec<u8>,
+
+    /// The part of the message being waited on.
+    state: ParseState,
+
+    /// The length of the buffer already parsed, up to the end of the last completed line or
+    /// chunk.
+    consumed: usize,
+
+    /// The offset of the request line, past any empty lines that preceded it.
+    start: usize,
+
+    /// The length of the header lines received so far, line terminators included.
+    header_bytes: usize,
+
+    /// The length of the chunk data received so far, for a chunked body.
+    body_received: usize,
+
+    /// How the body is framed, known once the headers are complete.
+    body_length: BodyLength,
+
+    /// The limits applied while parsing.
+    config: ParserConfig
+}
+
+impl Parser {
+
+    pub fn new() -> Self {
+        Self::with_config(ParserConfig::default())
+    }
+
+    /// Creates a parser that enforces the limits in `config`.
+    pub fn with_config(config: ParserConfig) -> Self {
+        Self {
+            buffer: Vec::new(),
+            state: ParseState::RequestLine,
+            consumed: 0,
+            start: 0,
+            header_bytes: 0,
+            body_received: 0,
+            body_length: BodyLength::None,
+            config
+        }
+    }
+
+    /// Appends `input` to the message and parses as far as it allows.
+    ///
+    /// Returns `Complete` with the length of the message once it has been fully received, and
+    /// `Partial` until then. Bytes fed after a complete message are kept but not parsed.
+    pub fn feed(&mut self, input: &[u8]) -> Result<Status, ParserError> {
+
+        self.buffer.extend_from_slice(input);
+
+        loop {
+            match self.state {
+                ParseState::RequestLine => {
+
+                    let end = match self.line_end() {
+                        Some(end) => end,
+                        None => return request_line_partial(&self.buffer[self.consumed..], &self.config)
+                    };
+
+                    // Empty lines before the request line are ignored, as `Request::parse` does
+                    // https://tools.ietf.org/html/rfc7230#section-3.5
+                    if matches!(crlf_or_lf(&self.buffer[self.consumed..end]), Ok(([], _))) {
+
+                        self.consumed = end;
+                        self.start = end;
+
+                        continue;
+                    }
+
+                    let mut request = Request::with_vec(Vec::new());
+
+                    let (_, simple) = request.parse_request_line(&self.buffer[self.consumed..end], &self.config)?;
+
+                    self.consumed = end;
+
+                    // HTTP/0.9 simple requests carry no headers or body
+                    self.state = match simple {
+                        true => ParseState::Complete,
+                        false => ParseState::Headers
+                    };
+                },
+                ParseState::Headers => {
+
+                    let pending = self.buffer.len() - self.consumed;
+
+                    let end = match self.line_end() {
+                        Some(end) => end,
+                        None if pending > self.config.max_header_bytes => return Err(ParserError::HeaderTooLarge),
+                        None if self.header_bytes + pending > self.config.max_headers_block_bytes => {
+                            return Err(ParserError::HeadersBlockTooLarge);
+                        },
+                        None => return Ok(Status::Partial)
+                    };
+
+                    match &self.buffer[self.consumed..end] {
+                        line if matches!(crlf_or_lf(line), Ok(([], _))) => {
+
+                            let mut request = Request::with_vec(Vec::new());
+
+                            // The lines were only delimited so far, parse them as a whole
+                            let head = &self.buffer[self.start..end];
+
+                            if request.parse_headers_only_with_config(head, &self.config)? != head.len() {
+                                return Err(ParserError::Headers);
+                            }
+
+                            self.body_length = request.body_length()?;
+                            self.state = ParseState::Body;
+                        },
+                        line if line.len() > self.config.max_header_bytes => return Err(ParserError::HeaderTooLarge),
+                        line => {
+
+                            // Checked line by line, so the buffer cannot grow past the limit
+                            self.header_bytes += line.len();
+
+                            if self.header_bytes > self.config.max_headers_block_bytes {
+                                return Err(ParserError::HeadersBlockTooLarge);
+                            }
+                        }
+                    };
+
+                    self.consumed = end;
+                },
+                ParseState::Body => match self.body_length {
+                    BodyLength::Fixed(length) if length > self.config.max_body_bytes => {
+                        return Err(ParserError::BodyTooLarge);
+                    },
+                    BodyLength::Fixed(length) => {
+
+                        let body_start = self.consumed;
+
+                        if self.buffer.len() - body_start < length {
+                            return Ok(Status::Partial);
+                        }
+
+                        self.consumed = body_start + length;
+                        self.state = ParseState::Complete;
+                    },
+                    BodyLength::Chunked => {
+
+                        let mut chunks = ChunkedDecoder::new(&self.buffer[self.consumed..]);
+
+                        loop {
+
+                            chunks.check_declared_size(self.body_received, self.config.max_body_bytes)?;
+
+                            match chunks.next() {
+                                Some(Ok(data)) => {
+
+                                    self.body_received += data.len();
+                                    self.consumed = self.buffer.len() - chunks.remaining().len();
+                                },
+                                Some(Err(_)) if chunks.needs_more_input() => return Ok(Status::Partial),
+                                Some(Err(e)) => return Err(e),
+                                None => break
+                            }
+                        }
+
+                        self.consumed = self.buffer.len() - chunks.remaining().len();
+                        self.state = ParseState::Complete;
+                    },
+                    _ => self.state = ParseState::Complete
+                },
+                ParseState::Complete => return Ok(Status::Complete(self.consumed))
+            }
+        }
+    }
+
+    /// The length of the input parsed so far, up to the end of the last completed line or
+    /// chunk, or of the whole message once it is complete.
+    ///
+    /// It never decreases, bytes fed past it are buffered and parsed again by the next `feed`.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// The part of the message being waited on.
+    pub fn state(&self) -> ParseState {
+        self.state
+    }
+
+    /// The parsed request, once the whole message has been received.
+    pub fn request(&self) -> Option<Request<'_>> {
+
+        if self.state != ParseState::Complete {
+            return None;
+        }
+
+        let mut request = Request::with_vec(Vec::new());
+
+        match request.parse_with_config(&self.buffer[self.start..self.consumed], &self.config) {
+            Ok(_) => Some(request),
+            Err(_) => None
+        }
+    }
+
+    /// The offset just past the next line terminator after the parsed part of the buffer.
+    fn line_end(&self) -> Option<usize> {
+        self.buffer[self.consumed..].iter().position(|&b| b == b'\n').map(|i| self.consumed + i + 1)
+    }
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,13 @@
+/// The part of a representation carried by a partial response, such as `bytes 0-499/1234`
+///
+/// https://tools.ietf.org/html/rfc7233#section-4.2
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct ContentRange {
+
+    /// The first and last byte positions, inclusive, `None` for an unsatisfied range such as
+    /// `bytes */1234`.
+    pub range: Option<(u64, u64)>,
+
+    /// The length of the complete representation, `None` when unknown, as in `bytes 0-499/*`.
+    pub total: Option<u64>
+}
@@ -1,10 +1,48 @@
 use std::fmt;
 use std::str;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::str::Utf8Error;
 use crate::http::parse::body;
+use crate::http::parse::host;
+use crate::http::parse::Stage;
+use crate::http::parse::Status;
+use crate::http::date::HttpDate;
 use crate::http::header::Header;
+use crate::http::body::BodyLength;
+use crate::http::parse::http_date;
+use crate::http::version::Version;
+use crate::http::auth::Credentials;
+use crate::http::etag::IfNoneMatch;
+use crate::http::parse::comma_list;
+use crate::http::parse::crlf_or_lf;
+use crate::http::media::ContentType;
+use crate::http::parse::HeaderSlots;
 use crate::http::parse::ParserError;
+use crate::http::parse::query_pairs;
+use crate::http::header::HeaderMatch;
+use crate::http::parse::content_type;
 use crate::http::parse::request_line;
+use crate::http::body::ChunkedDecoder;
+use crate::http::config::ParserConfig;
+use crate::http::header::EMPTY_HEADER;
+use crate::http::parse::authorization;
+use crate::http::parse::cache_control;
+use crate::http::parse::if_none_match;
+use crate::http::cache::CacheDirective;
+use crate::http::parse::content_length;
+use crate::http::parse::percent_decode;
+use crate::http::parse::request_target;
+use crate::http::target::RequestTarget;
+use crate::http::parse::accept_language;
+use crate::http::parse::headers_partial;
 use crate::http::parse::headers_iterator;
+use crate::http::parse::validate_version;
+use crate::http::parse::connection_tokens;
+use crate::http::parse::simple_request_line;
+use crate::http::parse::request_line_partial;
+#[cfg(feature = "compression")]
+use std::io::Read;
 
 #[derive(Debug, Default)]
 pub struct Request<'a> {
@@ -19,17 +57,34 @@ pub struct Request<'a> {
     pub(crate) version: &'a [u8],
 
     /// The request headers, such as `Host: subdomain.domain.tld`
-    pub(crate) headers: &'a mut [Header<'a>],
+    pub(crate) headers: HeaderStorage<'a>,
+
+    /// The number of headers populated by the last parse.
+    pub(crate) header_count: usize,
 
     /// The request body, such as `{\"dummy\": \"response\"}`
-    pub(crate) body: &'a [u8]
+    pub(crate) body: &'a [u8],
+
+    /// The part of the input consumed by the last parse, from the request line through the body.
+    pub(crate) raw: &'a [u8],
+
+    /// The trailer fields sent after a chunked body, such as `X-Checksum: abc`.
+    pub(crate) trailers: Vec<Header<'a>>
 
 }
 
 impl<'i> Request<'i> {
 
     pub fn new(headers: &'i mut [Header<'i>]) -> Self {
-        Self { headers, ..Default::default() }
+        Self { headers: HeaderStorage::Borrowed(headers), ..Default::default() }
+    }
+
+    /// Creates a request that parses its headers into `headers`, growing it as needed rather
+    /// than failing with `TooManyHeaders`.
+    ///
+    /// Any headers already held are replaced by the next parse.
+    pub fn with_vec(headers: Vec<Header<'i>>) -> Self {
+        Self { headers: HeaderStorage::Owned(headers), ..Default::default() }
     }
 
     pub fn method(&self) -> &[u8] {
@@ -44,115 +99,1128 @@ impl<'i> Request<'i> {
         self.version
     }
 
-    pub fn headers(&self) -> &[Header] {
+    /// The exact bytes consumed by the last parse, from the request line through the body, such
+    /// as for verifying a signature or forwarding the request verbatim.
+    ///
+    /// Parses that stop before the body, such as `parse_headers_only`, cover the request line and
+    /// headers only. Empty for a built request or when the last parse failed or was partial.
+    pub fn raw(&self) -> &'i [u8] {
+        self.raw
+    }
+
+    /// The request path without its query, such as `/events` for `/events?id=1`.
+    pub fn path_only(&self) -> &[u8] {
+        match self.path.iter().position(|&b| b == b'?') {
+            Some(i) => &self.path[..i],
+            None => self.path
+        }
+    }
 
-        // Since `headers` is an array with a fixed size, some of its entries could be blank,
-        // The parsed headers will not always fill it up completely, to remedy that, iterate over the array and return slice of
-        // length 0 to fist blank entry
-        let mut length = 0;
+    /// The request path without its query, with percent-encoded octets decoded, such as
+    /// `/✓` for `/%E2%9C%93`.
+    ///
+    /// Escapes that make up a multi-byte character are decoded together, and raw non-ASCII
+    /// bytes are kept, so the result fails with `InvalidUtf8Content` unless it is valid UTF-8.
+    ///
+    /// https://tools.ietf.org/html/rfc3987#section-3.1
+    pub fn path_decoded(&self) -> Result<String, ParserError> {
+        match String::from_utf8(percent_decode(self.path_only())?) {
+            Ok(decoded) => Ok(decoded),
+            Err(e) => Err(ParserError::InvalidUtf8Content(e.utf8_error()))
+        }
+    }
 
-        for (i, elem) in self.headers.iter().enumerate() {
-            if elem.name.len() == 0 && elem.value.len() == 0 {
-                length = i;
-                break;
+    /// The raw query following the first `?` of the path, such as `id=1` for `/events?id=1`.
+    ///
+    /// `None` when the path has no `?`, an empty slice when it ends with one.
+    pub fn query(&self) -> Option<&[u8]> {
+        self.path.iter().position(|&b| b == b'?').map(|i| &self.path[i + 1..])
+    }
+
+    /// The `name=value` pairs of the query, as received.
+    pub fn query_pairs(&self) -> Vec<(&[u8], &[u8])> {
+        self.query().map(query_pairs).unwrap_or_default()
+    }
+
+    /// The `name=value` pairs of the query, with `+` read as a space and percent-encoded octets
+    /// decoded, as is done for `application/x-www-form-urlencoded` data.
+    ///
+    /// https://url.spec.whatwg.org/#application/x-www-form-urlencoded
+    pub fn query_pairs_decoded(&self) -> Result<Vec<(String, String)>, ParserError> {
+
+        let decode = |input: &[u8]| -> Result<String, ParserError> {
+
+            let input = input.iter().map(|&b| if b == b'+' { b' ' } else { b }).collect::<Vec<_>>();
+
+            match String::from_utf8(percent_decode(&input)?) {
+                Ok(decoded) => Ok(decoded),
+                Err(e) => Err(ParserError::InvalidUtf8Content(e.utf8_error()))
             }
+        };
+
+        self.query_pairs()
+            .into_iter()
+            .map(|(name, value)| Ok((decode(name)?, decode(value)?)))
+            .collect()
+    }
+
+    /// The request method as a string slice, such as `GET`.
+    pub fn method_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.method)
+    }
+
+    /// The request path as a string slice, such as `/events`.
+    ///
+    /// The path may carry arbitrary percent-encoded bytes, so this can fail.
+    pub fn path_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.path)
+    }
+
+    /// The request version as a string slice, such as `1.1`.
+    pub fn version_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.version)
+    }
+
+    /// The request version as `(major, minor)`, such as `(1, 1)` for `HTTP/1.1`.
+    ///
+    /// The minor version of `HTTP/2` and `HTTP/3` is `0`. A malformed version fails with
+    /// `InvalidVersion`, as `Version::from_bytes` does.
+    pub fn version_tuple(&self) -> Result<(u8, u8), ParserError> {
+        Version::from_bytes(self.version).map(|version| (version.major, version.minor))
+    }
+
+    /// Classifies the request path into one of the four request target forms, along with its
+    /// components.
+    ///
+    /// `None` when the path matches none of the forms.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-5.3
+    pub fn target_form(&self) -> Option<RequestTarget<'_>> {
+        match request_target(self.method, self.path) {
+            Ok(([], target)) => Some(target),
+            _ => None
+        }
+    }
+
+    /// The number of headers populated by the last parse.
+    pub fn header_count(&self) -> usize {
+        self.header_count
+    }
+
+    pub fn headers(&self) -> &[Header<'_>] {
+        &self.headers[..self.populated_headers()]
+    }
+
+    /// The parsed headers, mutably, so they can be rewritten or replaced after parsing.
+    ///
+    /// Names and values still borrow the original input buffer, and any replacement must live
+    /// as long as it does.
+    pub fn headers_mut(&mut self) -> &mut [Header<'i>] {
+
+        let length = self.populated_headers();
+
+        &mut self.headers[..length]
+    }
+
+    fn populated_headers(&self) -> usize {
+
+        // Entries past the parsed headers are blank or left over from an earlier parse, and a
+        // parsed header may itself be blank, so the stored count is trusted over their contents
+        self.header_count.min(self.headers.len())
+    }
+
+    /// The trailer fields sent after the last chunk of a chunked body, such as a checksum
+    /// announced by the `Trailer` header.
+    ///
+    /// Empty when the body was not chunked or carried no trailer fields.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-4.1.2
+    pub fn trailers(&self) -> &[Header<'_>] {
+        &self.trailers
+    }
+
+    /// Iterates over every parsed header in the order it was received.
+    ///
+    /// Unlike `header`, which returns only the first match for a name, nothing is deduplicated
+    /// or reordered, so the header block can be reproduced faithfully.
+    pub fn raw_headers(&self) -> std::slice::Iter<'_, Header<'_>> {
+        self.headers().iter()
+    }
+
+    /// Returns the first header whose name matches `name`, ignoring ASCII case.
+    ///
+    /// Headers that may legitimately repeat, such as `Set-Cookie`, should be read with
+    /// `headers_all` instead.
+    pub fn header(&self, name: &str) -> Option<&Header<'_>> {
+        self.header_with_mode(name, HeaderMatch::default())
+    }
+
+    /// Returns the first header whose name matches `name`, compared as `mode` requires.
+    pub fn header_with_mode(&self, name: &str, mode: HeaderMatch) -> Option<&Header<'_>> {
+        self.headers().iter().find(|h| h.name_matches(name.as_bytes(), mode))
+    }
+
+    /// Returns the raw value of the first header whose name matches `name`, ignoring ASCII case.
+    ///
+    /// This is the lookup underlying the accessors for structured values, such as `host`.
+    pub fn header_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.header(name).map(|h| h.value)
+    }
+
+    /// Returns the value of every header whose name matches `name`, ignoring ASCII case, in the
+    /// order they appear in the message.
+    ///
+    /// Values are yielded exactly as received. Separate headers are not folded into a single
+    /// comma-joined value, and a single header carrying a list (`Via: 1.0 a, 1.1 b`) is yielded
+    /// as one value rather than split on its commas.
+    pub fn headers_all<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'s [u8]> + 's {
+        self.headers()
+            .iter()
+            .filter(move |h| h.name_eq(name.as_bytes()))
+            .map(|h| h.value)
+    }
+
+    /// Joins the values of every header whose name matches `name` with `, `, in the order they
+    /// appear in the message.
+    ///
+    /// `None` when the header is absent. `Set-Cookie` values cannot be combined this way and
+    /// always return `None`, read them with `headers_all` instead.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.2.2
+    pub fn header_combined(&self, name: &str) -> Option<Vec<u8>> {
+
+        if name.eq_ignore_ascii_case("Set-Cookie") {
+            return None;
+        }
+
+        let values = self.headers_all(name).collect::<Vec<_>>();
+
+        match values.is_empty() {
+            true => None,
+            false => Some(values.join(&b", "[..]))
+        }
+    }
+
+    /// Returns the declared body size from the `Content-Length` header.
+    ///
+    /// `None` when the header is absent, `Some(Err(_))` when its value is not a valid length.
+    pub fn content_length(&self) -> Option<Result<usize, ParserError>> {
+        self.header_bytes("Content-Length").map(content_length)
+    }
+
+    /// Determines how the body is framed, following the precedence of RFC 7230.
+    ///
+    /// A request is never close-delimited, without `Transfer-Encoding` or `Content-Length` it
+    /// has no body.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.3.3
+    pub fn body_length(&self) -> Result<BodyLength, ParserError> {
+
+        self.check_framing()?;
+
+        if self.header("Transfer-Encoding").is_some() {
+            return match self.is_chunked() {
+                true => Ok(BodyLength::Chunked),
+                false => Err(ParserError::TransferEncoding)
+            };
+        }
+
+        match self.content_length() {
+            Some(length) => Ok(BodyLength::Fixed(length?)),
+            None => Ok(BodyLength::None)
+        }
+    }
+
+    /// Whether the body uses chunked framing, that is `chunked` is the final coding listed in
+    /// `Transfer-Encoding`.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.3.1
+    pub fn is_chunked(&self) -> bool {
+        match self.headers_all("Transfer-Encoding").last() {
+            Some(value) => match value.rsplit(|&b| b == b',').next() {
+                Some(coding) => coding.trim_ascii().eq_ignore_ascii_case(b"chunked"),
+                None => false
+            },
+            None => false
+        }
+    }
+
+    /// Returns every option listed in the `Connection` headers, in order.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-6.1
+    pub fn connection_tokens(&self) -> Vec<&[u8]> {
+        self.headers_all("Connection").flat_map(connection_tokens).collect()
+    }
+
+    /// Returns every protocol listed in the `Upgrade` headers, in order of preference, such as
+    /// `websocket` or `HTTP/2.0`.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-6.7
+    pub fn upgrade_protocols(&self) -> Vec<&[u8]> {
+        self.headers_all("Upgrade").flat_map(connection_tokens).collect()
+    }
+
+    /// The value of the non-standard `Proxy-Connection` header, such as `keep-alive`.
+    pub fn proxy_connection(&self) -> Option<&[u8]> {
+        self.header_bytes("Proxy-Connection")
+    }
+
+    /// Whether the connection should stay open once this request has been answered.
+    ///
+    /// A `close` option always closes it. Otherwise HTTP/1.1 and later keep it open by default,
+    /// while HTTP/1.0 and earlier close it unless the `keep-alive` option is given.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-6.3
+    pub fn is_keep_alive(&self) -> bool {
+        self.is_keep_alive_with_config(&ParserConfig::default())
+    }
+
+    /// Whether the connection should stay open, like `is_keep_alive`, with `Proxy-Connection`
+    /// also taken into account when `config.honor_proxy_connection` is set.
+    ///
+    /// `Connection` takes precedence, `Proxy-Connection` only decides when `Connection` gives
+    /// neither `close` nor `keep-alive`.
+    pub fn is_keep_alive_with_config(&self, config: &ParserConfig) -> bool {
+
+        let mut tokens = self.connection_tokens();
+
+        let has = |tokens: &[&[u8]], option: &[u8]| tokens.iter().any(|t| t.eq_ignore_ascii_case(option));
+
+        if !has(&tokens, b"close") && !has(&tokens, b"keep-alive") && config.honor_proxy_connection {
+            tokens.extend(self.proxy_connection().map(connection_tokens).unwrap_or_default());
+        }
+
+        if has(&tokens, b"close") {
+            return false;
+        }
+
+        match self.version_tuple() {
+            Ok(version) if version >= (1, 1) => true,
+            Ok(_) => has(&tokens, b"keep-alive"),
+            Err(_) => false
         }
+    }
+
+    /// Returns the media type and parameters from the `Content-Type` header.
+    ///
+    /// `None` when the header is absent or malformed.
+    pub fn content_type(&self) -> Option<ContentType<'_>> {
+        match content_type(self.header_bytes("Content-Type")?) {
+            Ok((_, content_type)) => Some(content_type),
+            Err(_) => None
+        }
+    }
+
+    /// Returns the directives of every `Cache-Control` header, in order.
+    ///
+    /// https://tools.ietf.org/html/rfc7234#section-5.2.1
+    pub fn cache_control(&self) -> Vec<CacheDirective<'_>> {
+        self.headers_all("Cache-Control").flat_map(cache_control).collect()
+    }
+
+    /// Returns the language ranges from the `Accept-Language` header, most preferred first.
+    ///
+    /// Empty when the header is absent.
+    pub fn accept_language(&self) -> Vec<(&[u8], f32)> {
+        match self.header_bytes("Accept-Language") {
+            Some(value) => accept_language(value),
+            None => Vec::new()
+        }
+    }
+
+    /// Returns the host and optional port from the `Host` header.
+    ///
+    /// `None` when the header is absent or malformed, including a port that is not a valid
+    /// `u16`.
+    pub fn host(&self) -> Option<(&[u8], Option<u16>)> {
+        match host(self.header_bytes("Host")?) {
+            Ok((b"", host)) => Some(host),
+            Ok(_) => None,
+            Err(_) => None
+        }
+    }
+
+    /// Returns every address listed in the `X-Forwarded-For` headers, in order.
+    ///
+    /// The first address is the original client, each proxy appends the address it received
+    /// the request from.
+    pub fn forwarded_for(&self) -> Vec<&[u8]> {
+        self.headers_all("X-Forwarded-For").flat_map(comma_list).collect()
+    }
+
+    /// Returns the date from the `If-Modified-Since` header.
+    ///
+    /// `None` when the header is absent or its date is malformed, since an invalid date must be
+    /// ignored rather than rejected.
+    ///
+    /// https://tools.ietf.org/html/rfc7232#section-3.3
+    pub fn if_modified_since(&self) -> Option<HttpDate> {
+        match http_date(self.header_bytes("If-Modified-Since")?) {
+            Ok((_, date)) => Some(date),
+            Err(_) => None
+        }
+    }
+
+    /// Returns the entity-tags from the `If-None-Match` header.
+    ///
+    /// `None` when the header is absent or malformed.
+    ///
+    /// https://tools.ietf.org/html/rfc7232#section-3.2
+    pub fn if_none_match(&self) -> Option<IfNoneMatch<'_>> {
+        match if_none_match(self.header_bytes("If-None-Match")?) {
+            Ok((_, condition)) => Some(condition),
+            Err(_) => None
+        }
+    }
+
+    /// Returns the date from the `If-Unmodified-Since` header.
+    ///
+    /// `None` when the header is absent or its date is malformed, since an invalid date must be
+    /// ignored rather than rejected.
+    ///
+    /// https://tools.ietf.org/html/rfc7232#section-3.4
+    pub fn if_unmodified_since(&self) -> Option<HttpDate> {
+        match http_date(self.header_bytes("If-Unmodified-Since")?) {
+            Ok((_, date)) => Some(date),
+            Err(_) => None
+        }
+    }
 
-        &self.headers[..length]
+    /// Returns the credentials from the `Authorization` header.
+    ///
+    /// `None` when the header is absent or malformed.
+    pub fn authorization(&self) -> Option<Credentials<'_>> {
+        match authorization(self.header_bytes("Authorization")?) {
+            Ok((_, credentials)) => Some(credentials),
+            Err(_) => None
+        }
     }
 
+    /// The body as received.
+    ///
+    /// For a chunked request these are the raw framed bytes, chunk sizes and trailer fields
+    /// included, `transfer_decoded_body` removes the framing.
     pub fn body(&self) -> &[u8] {
         self.body
     }
 
+    /// Returns the body and leaves an empty one in its place, so it is only processed once.
+    pub fn take_body(&mut self) -> &'i [u8] {
+        std::mem::take(&mut self.body)
+    }
+
+    /// Returns the body with its `Content-Encoding` removed.
+    ///
+    /// A chunked body is de-chunked first, as by `transfer_decoded_body`. `gzip` and `deflate`
+    /// bodies are inflated, a body without a `Content-Encoding` (or with `identity`) is returned
+    /// unchanged. Any other coding fails with `ParserError::Decompression`.
+    #[cfg(feature = "compression")]
+    pub fn decoded_body(&self) -> Result<Vec<u8>, ParserError> {
+
+        let body = match self.is_chunked() {
+            true => self.transfer_decoded_body()?,
+            false => self.body.to_vec()
+        };
+
+        let encoding = match self.header_bytes("Content-Encoding") {
+            Some(value) => value,
+            None => return Ok(body)
+        };
+
+        if encoding.eq_ignore_ascii_case(b"identity") {
+            return Ok(body);
+        }
+
+        match decompress(encoding, &body) {
+            Some(Ok(decoded)) => Ok(decoded),
+            _ => Err(ParserError::Decompression)
+        }
+    }
+
+    /// Returns the body with every coding listed in `Transfer-Encoding` removed, the last
+    /// listed coding first, so `gzip, chunked` is de-chunked and then inflated.
+    ///
+    /// `chunked` and `identity` are always understood, `gzip` and `deflate` need the
+    /// `compression` feature. Any other coding fails with `UnsupportedTransferEncoding`.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.3.1
+    pub fn transfer_decoded_body(&self) -> Result<Vec<u8>, ParserError> {
+
+        let codings = self.headers_all("Transfer-Encoding").flat_map(comma_list).collect::<Vec<_>>();
+
+        let mut decoded = self.body.to_vec();
+
+        for coding in codings.into_iter().rev() {
+
+            if coding.eq_ignore_ascii_case(b"chunked") {
+                decoded = ChunkedDecoder::new(&decoded).collect::<Result<Vec<_>, _>>()?.concat();
+                continue;
+            }
+
+            if coding.eq_ignore_ascii_case(b"identity") {
+                continue;
+            }
+
+            #[cfg(feature = "compression")]
+            {
+                if let Some(result) = decompress(coding, &decoded) {
+                    decoded = result.map_err(|_| ParserError::Decompression)?;
+                    continue;
+                }
+            }
+
+            return Err(ParserError::UnsupportedTransferEncoding);
+        }
+
+        Ok(decoded)
+    }
+
     pub fn parse<'r: 'i>(&mut self, input: &'i [u8]) -> Result<(), ParserError> {
+        self.parse_with_config(input, &ParserConfig::default())
+    }
+
+    /// Parses the request, enforcing the limits in `config`.
+    pub fn parse_with_config(&mut self, input: &'i [u8], config: &ParserConfig) -> Result<(), ParserError> {
+        self.parse_message(input, config).map(|_| ())
+    }
 
-        let mut unparsed_input;
+    /// Parses the request and returns the input that follows it, such as the next pipelined
+    /// request.
+    pub fn parse_remaining(&mut self, input: &'i [u8]) -> Result<&'i [u8], ParserError> {
+        self.parse_message(input, &ParserConfig::default())
+    }
 
-        // Request line
-        match request_line(input) {
-            Ok((input, (method, path, version, _))) => {
+    /// Parses every pipelined request in `input`, in order.
+    ///
+    /// Each request takes as many entries of `arena` as it has headers, the next request is
+    /// parsed into the entries that remain. Parsing stops once the input is exhausted or what
+    /// remains is not yet a complete request, whose line, headers or body are cut short.
+    ///
+    /// Returns the complete requests along with the offset in `input` where parsing stopped,
+    /// where the next call should resume once more input is available.
+    pub fn parse_all(input: &'i [u8], arena: &'i mut [Header<'i>]) -> Result<(Vec<Request<'i>>, usize), ParserError> {
 
-                self.method = method;
-                self.path = path;
-                self.version = version;
+        let mut requests = Vec::new();
+        let mut offset = 0;
+        let mut arena = arena;
 
-                unparsed_input = input;
-            },
-            Err(_) => return Err(ParserError::RequestLine)
+        loop {
+
+            // Empty lines between pipelined requests are ignored
+            // https://tools.ietf.org/html/rfc7230#section-3.5
+            while let Ok((rest, _)) = crlf_or_lf(&input[offset..]) {
+                offset = input.len() - rest.len();
+            }
+
+            if offset == input.len() {
+                break;
+            }
+
+            let mut request = Request::new(std::mem::take(&mut arena));
+
+            match request.parse_partial(&input[offset..])? {
+                Status::Complete(length) => offset += length,
+                Status::Partial => break
+            }
+
+            let count = request.header_count();
+
+            if let HeaderStorage::Borrowed(headers) = std::mem::take(&mut request.headers) {
+
+                let (used, unused) = headers.split_at_mut(count);
+
+                request.headers = HeaderStorage::Borrowed(used);
+                arena = unused;
+            }
+
+            requests.push(request);
+        }
+
+        Ok((requests, offset))
+    }
+
+    /// Parses a request from a buffer that may not hold all of it yet.
+    ///
+    /// Returns `Partial` when the request line, headers or body are cut short, with
+    /// `header_count()` reporting the headers completed so far. Calling it again with the
+    /// buffer extended parses the request from the start.
+    pub fn parse_partial(&mut self, input: &'i [u8]) -> Result<Status, ParserError> {
+        self.parse_partial_with_config(input, &ParserConfig::default())
+    }
+
+    /// Parses a request from a buffer that may not hold all of it yet, enforcing the limits in
+    /// `config`.
+    ///
+    /// A chunked body fails with `BodyTooLarge` as soon as a chunk declares a size past
+    /// `max_body_bytes`, without waiting for its data.
+    pub fn parse_partial_with_config(&mut self, input: &'i [u8], config: &ParserConfig) -> Result<Status, ParserError> {
+
+        let (unparsed_input, simple) = match self.parse_request_line(input, config) {
+            Ok(parsed) => parsed,
+            Err(ParserError::Syntax { .. }) if !input.contains(&b'\n') => return request_line_partial(input, config),
+            Err(e) => return Err(e)
         };
 
+        if simple {
+
+            self.raw = &input[..input.len() - unparsed_input.len()];
+
+            return Ok(Status::Complete(self.raw.len()));
+        }
+
         // Headers
-        match headers_iterator(unparsed_input, self.headers) {
-            Ok((input, _)) => unparsed_input = input,
-            Err(_) => return Err(ParserError::Headers)
+        let mut slots = CountedSlots::new(&mut self.headers);
+        let headers = headers_partial(unparsed_input, &mut slots, config);
+
+        self.header_count = slots.count;
+
+        let unparsed_input = match headers? {
+            Some(input) => input,
+            None => return Ok(Status::Partial)
+        };
+
+        // Content
+        let length = match self.body_length()? {
+            BodyLength::Fixed(length) if length > config.max_body_bytes => return Err(ParserError::BodyTooLarge),
+            BodyLength::Fixed(length) if length > unparsed_input.len() => return Ok(Status::Partial),
+            BodyLength::Fixed(length) => length,
+            BodyLength::Chunked => {
+
+                let mut chunks = ChunkedDecoder::new(unparsed_input);
+                let mut length = 0usize;
+
+                loop {
+
+                    chunks.check_declared_size(length, config.max_body_bytes)?;
+
+                    match chunks.next() {
+                        Some(Ok(data)) => length += data.len(),
+                        Some(Err(_)) if chunks.needs_more_input() => return Ok(Status::Partial),
+                        Some(Err(e)) => return Err(e),
+                        None => break
+                    }
+                }
+
+                self.parse_trailers(chunks.trailers(), config)?;
+
+                unparsed_input.len() - chunks.remaining().len()
+            },
+            _ => 0
         };
 
+        self.body = &unparsed_input[..length];
+        self.raw = &input[..input.len() - unparsed_input.len() + length];
+
+        Ok(Status::Complete(input.len() - unparsed_input.len() + length))
+    }
+
+    /// Parses the request line, headers and body, returning the input that follows them.
+    fn parse_message(&mut self, input: &'i [u8], config: &ParserConfig) -> Result<&'i [u8], ParserError> {
+
+        let mut unparsed_input = self.parse_head(input, config)?;
+
         // Content
-        // Check for Content-Length or Transfer-Encoding to determine if request has a body
-        if let Some(header) = self.headers.iter().find(|&h| {
+        match self.body_length()? {
+            BodyLength::Fixed(length) if length > config.max_body_bytes => return Err(ParserError::BodyTooLarge),
+            BodyLength::Fixed(length) if length > 0 => match body(length, unparsed_input) {
+                Ok((input, body)) => {
+
+                    self.body = body;
+
+                    unparsed_input = input;
+                },
+                Err(_) => return Err(ParserError::IncompleteBody { expected: length, got: unparsed_input.len() })
+            },
+            BodyLength::Chunked => {
+
+                let mut chunks = ChunkedDecoder::new(unparsed_input);
+                let mut length = 0;
+
+                for data in chunks.by_ref() {
+
+                    length += data?.len();
+
+                    if length > config.max_body_bytes {
+                        return Err(ParserError::BodyTooLarge);
+                    }
+                }
+
+                self.body = &unparsed_input[..unparsed_input.len() - chunks.remaining().len()];
+
+                self.parse_trailers(chunks.trailers(), config)?;
+
+                unparsed_input = chunks.remaining();
+            },
+            _ => {}
+        };
+
+        self.raw = &input[..input.len() - unparsed_input.len()];
+
+        Ok(unparsed_input)
+    }
+
+    /// Parses the request line and headers, then hands the body to `on_body_chunk` instead of
+    /// storing it in `body`.
+    ///
+    /// A `Content-Length` body is delivered as a single slice, a chunked body is delivered one
+    /// chunk at a time, in order.
+    pub fn parse_streaming<F>(&mut self, input: &'i [u8], mut on_body_chunk: F) -> Result<(), ParserError>
+    where
+        F: FnMut(&[u8])
+    {
+
+        let unparsed_input = self.parse_head(input, &ParserConfig::default())?;
+
+        let remaining = match self.body_length()? {
+            BodyLength::Fixed(length) if length > 0 => match body(length, unparsed_input) {
+                Ok((remaining, body)) => {
+
+                    on_body_chunk(body);
+
+                    remaining
+                },
+                Err(_) => return Err(ParserError::IncompleteBody { expected: length, got: unparsed_input.len() })
+            },
+            BodyLength::Chunked => {
+
+                let mut chunks = ChunkedDecoder::new(unparsed_input);
+
+                for data in chunks.by_ref() {
+                    on_body_chunk(data?);
+                }
+
+                self.parse_trailers(chunks.trailers(), &ParserConfig::default())?;
+
+                chunks.remaining()
+            },
+            _ => unparsed_input
+        };
+
+        self.raw = &input[..input.len() - remaining.len()];
+
+        Ok(())
+    }
+
+    /// Parses the request line and headers only, returning the offset in `input` where the
+    /// body begins.
+    ///
+    /// The body is left unread so it can be streamed by the caller, `body` stays empty.
+    pub fn parse_headers_only(&mut self, input: &'i [u8]) -> Result<usize, ParserError> {
+        self.parse_headers_only_with_config(input, &ParserConfig::default())
+    }
+
+    /// Parses the request line and headers only, like `parse_headers_only`, enforcing the
+    /// limits in `config`.
+    pub fn parse_headers_only_with_config(&mut self, input: &'i [u8], config: &ParserConfig) -> Result<usize, ParserError> {
+
+        let unparsed_input = self.parse_head(input, config)?;
+
+        self.raw = &input[..input.len() - unparsed_input.len()];
+
+        Ok(input.len() - unparsed_input.len())
+    }
+
+    /// Starts building a request without parsing one, `GET / HTTP/1.1` until set otherwise.
+    pub fn build() -> RequestBuilder<'i> {
+        RequestBuilder {
+            request: Request { method: b"GET", path: b"/", version: b"1.1", ..Request::with_vec(Vec::new()) }
+        }
+    }
+
+    /// The request as it was received, for logging, with bytes that are not valid UTF-8 replaced
+    /// by `U+FFFD`.
+    ///
+    /// Unlike `to_bytes` no header is added, lines end with a line feed alone and the body is only
+    /// shown when there is one.
+    pub fn as_str_lossy(&self) -> String {
+
+        let mut output = format!(
+            "{} {} HTTP/{}\n",
+            String::from_utf8_lossy(self.method),
+            String::from_utf8_lossy(self.path),
+            String::from_utf8_lossy(self.version)
+        );
+
+        for header in self.headers() {
+            output.push_str(&format!("{}: {}\n", String::from_utf8_lossy(header.name), String::from_utf8_lossy(header.value)));
+        }
+
+        if !self.body.is_empty() {
+            output.push('\n');
+            output.push_str(&String::from_utf8_lossy(self.body));
+        }
+
+        output
+    }
 
-            // https://tools.ietf.org/html/rfc7230#section-3.3.2
-            h.name() == &b"Content-Length"[..] && h.value() > &b"0"[..] || h.name() == &b"Transfer-Encoding"[..]
+    /// Serializes the request to its wire format, the request line, headers, empty line and body.
+    ///
+    /// A `Content-Length` header is added for a non-empty body when neither `Content-Length`
+    /// nor `Transfer-Encoding` is present.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3
+    pub fn to_bytes(&self) -> Vec<u8> {
 
-        }) {
+        let mut bytes = Vec::with_capacity(64 + self.body.len());
 
-            if header.name() == &b"Content-Length"[..] {
+        bytes.extend_from_slice(self.method);
+        bytes.push(b' ');
+        bytes.extend_from_slice(self.path);
+        bytes.extend_from_slice(b" HTTP/");
+        bytes.extend_from_slice(self.version);
+        bytes.extend_from_slice(b"\r\n");
 
-                let length = str::from_utf8(header.value())?;
+        for header in self.headers() {
+            bytes.extend_from_slice(header.name);
+            bytes.extend_from_slice(b": ");
+            bytes.extend_from_slice(header.value);
+            bytes.extend_from_slice(b"\r\n");
+        }
+
+        let framed = self.header("Content-Length").is_some() || self.header("Transfer-Encoding").is_some();
 
-                let length = length.parse::<usize>()?;
+        if !self.body.is_empty() && !framed {
+            bytes.extend_from_slice(format!("Content-Length: {}\r\n", self.body.len()).as_bytes());
+        }
+
+        bytes.extend_from_slice(b"\r\n");
+        bytes.extend_from_slice(self.body);
+
+        bytes
+    }
+
+    /// Copies the parsed request into an `OwnedRequest` that no longer borrows the input.
+    pub fn to_owned(&self) -> OwnedRequest {
+        OwnedRequest {
+            method: self.method.to_vec(),
+            path: self.path.to_vec(),
+            version: self.version.to_vec(),
+            headers: self.headers().iter().map(|h| (h.name.to_vec(), h.value.to_vec())).collect(),
+            body: self.body.to_vec()
+        }
+    }
+
+    /// Clears the parsed request so its header storage can be reused for the next parse.
+    pub fn reset(&mut self) {
+
+        self.method = b"";
+        self.path = b"";
+        self.version = b"";
+        self.body = b"";
+        self.raw = b"";
+        self.header_count = 0;
+        self.trailers.clear();
+
+        match self.headers {
+            HeaderStorage::Borrowed(ref mut headers) => {
+                for header in headers.iter_mut() {
+                    *header = EMPTY_HEADER;
+                }
+            },
+            HeaderStorage::Owned(ref mut headers) => headers.clear()
+        }
+    }
 
-                match body(length, unparsed_input) {
-                    Ok((_, body)) => {
+    /// Rejects a message that frames its body with both `Content-Length` and `Transfer-Encoding`,
+    /// or with several `Content-Length` headers that disagree.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.3.2
+    /// https://tools.ietf.org/html/rfc7230#section-3.3.3
+    fn check_framing(&self) -> Result<(), ParserError> {
 
-                        self.body = body;
+        let mut content_length: Option<&[u8]> = None;
 
-                    },
-                    Err(_) => return Err(ParserError::Body)
-                };
+        for header in self.headers().iter().filter(|h| h.name_eq(b"Content-Length")) {
+            match content_length {
+                Some(value) if value != header.value() => return Err(ParserError::ConflictingContentLength),
+                _ => content_length = Some(header.value())
             }
+        }
+
+        let transfer_encoding = self.headers().iter().any(|h| h.name_eq(b"Transfer-Encoding"));
 
-            // @TODO: implement this
-            if header.name() == &b"Transfer-Encoding"[..] {}
+        if content_length.is_some() && transfer_encoding {
+            return Err(ParserError::ConflictingFraming);
         }
 
         Ok(())
     }
-}
 
-impl<'a> fmt::Display for Request<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    /// Parses the trailer fields that followed a chunked body.
+    fn parse_trailers(&mut self, trailers: &'i [u8], config: &ParserConfig) -> Result<(), ParserError> {
 
-        let mut headers = String::new();
+        self.trailers.clear();
 
-        let headers_length = self.headers().len();
+        headers_iterator(trailers, &mut self.trailers, config).map(|_| ())
+    }
+
+    /// Parses the request line and headers, returning the input that follows them.
+    fn parse_head(&mut self, input: &'i [u8], config: &ParserConfig) -> Result<&'i [u8], ParserError> {
 
-        for (i, element) in self.headers().iter().enumerate() {
+        let (unparsed_input, simple) = self.parse_request_line(input, config)?;
 
-            let header;
+        // HTTP/0.9 simple requests carry no headers
+        if simple {
+            return Ok(unparsed_input);
+        }
 
-            if i != (headers_length - 1) {
+        // Headers
+        let mut slots = CountedSlots::new(&mut self.headers);
+        let unparsed_input = headers_iterator(unparsed_input, &mut slots, config)?;
 
-                header = format!("{}: {}, ", str::from_utf8(element.name).unwrap(), str::from_utf8(element.value).unwrap());
+        self.header_count = slots.count;
 
-            } else {
+        Ok(unparsed_input)
+    }
 
-                header = format!("{}: {}", str::from_utf8(element.name).unwrap(), str::from_utf8(element.value).unwrap());
+    /// Clears what a previous parse left behind and parses the request line, returning the
+    /// input that follows it and whether it is an HTTP/0.9 simple request line.
+    ///
+    /// Simple request lines are only accepted when `config.allow_http09` is set.
+    pub(crate) fn parse_request_line(&mut self, input: &'i [u8], config: &ParserConfig) -> Result<(&'i [u8], bool), ParserError> {
 
-            }
+        self.raw = b"";
+        self.body = b"";
+        self.trailers.clear();
 
-            headers.push_str(&header)
+        // Headers left from a previous parse are dropped first
+        if let HeaderStorage::Owned(ref mut headers) = self.headers {
+            headers.clear();
+        }
+
+        self.header_count = 0;
+
+        // Request line
+        let (unparsed_input, (method, path, version), simple) = match request_line(input) {
+            Ok((input, (method, path, version, _))) => (input, (method, path, version), false),
+            Err(_) if config.allow_http09 => match simple_request_line(input) {
+                Ok((input, (method, path))) => (input, (method, path, &b"0.9"[..]), true),
+                Err(e) => return Err(ParserError::syntax(Stage::RequestLine, e))
+            },
+            Err(e) => return Err(ParserError::syntax(Stage::RequestLine, e))
         };
 
-        write!(
-            f,
-            "Request {{ method: {}, path: {}, version: HTTP/{}, headers: {{ {} }} }}",
-            str::from_utf8(self.method).unwrap(),
-            str::from_utf8(self.path).unwrap(),
-            str::from_utf8(self.version).unwrap(),
-            headers
-        )
+        if method.len() > config.max_method_bytes {
+            return Err(ParserError::MethodTooLong);
+        }
+
+        if path.len() > config.max_request_target_bytes {
+            return Err(ParserError::RequestTargetTooLong);
+        }
+
+        validate_version(version)?;
+
+        self.method = method;
+        self.path = path;
+        self.version = version;
+
+        Ok((unparsed_input, simple))
+    }
+}
+
+/// Inflates `data` compressed with `coding`, `None` when the coding is not a compression
+///
+/// https://tools.ietf.org/html/rfc7230#section-4.2
+#[cfg(feature = "compression")]
+fn decompress(coding: &[u8], data: &[u8]) -> Option<std::io::Result<Vec<u8>>> {
+
+    let mut decoded = Vec::new();
+
+    let result = if coding.eq_ignore_ascii_case(b"gzip") || coding.eq_ignore_ascii_case(b"x-gzip") {
+        flate2::read::GzDecoder::new(data).read_to_end(&mut decoded)
+    } else if coding.eq_ignore_ascii_case(b"deflate") {
+        flate2::read::ZlibDecoder::new(data).read_to_end(&mut decoded)
+    } else {
+        return None;
+    };
+
+    Some(result.map(|_| decoded))
+}
+
+/// Builds a `Request` field by field, as returned by `Request::build`
+#[derive(Debug)]
+pub struct RequestBuilder<'a> {
+    request: Request<'a>
+}
+
+impl<'a> RequestBuilder<'a> {
+
+    /// Sets the method, such as `POST`.
+    pub fn method(mut self, method: &'a str) -> Self {
+        self.request.method = method.as_bytes();
+        self
+    }
+
+    /// Sets the request target, such as `/events?id=1`.
+    pub fn path(mut self, path: &'a str) -> Self {
+        self.request.path = path.as_bytes();
+        self
+    }
+
+    /// Sets the version as it follows `HTTP/`, such as `1.1`.
+    pub fn version(mut self, version: &'a str) -> Self {
+        self.request.version = version.as_bytes();
+        self
+    }
+
+    /// Appends a header, keeping any header already added with the same name.
+    pub fn header(mut self, name: &'a str, value: &'a str) -> Self {
+
+        self.request.headers.store(self.request.header_count, Header { name: name.as_bytes(), value: value.as_bytes() });
+        self.request.header_count += 1;
+
+        self
+    }
+
+    /// Sets the body, `to_bytes` adds its `Content-Length` unless a framing header is set.
+    pub fn body(mut self, body: &'a [u8]) -> Self {
+        self.request.body = body;
+        self
+    }
+
+    pub fn build(self) -> Request<'a> {
+        self.request
+    }
+}
+
+/// Where a request stores its headers, a caller supplied slice or a growable Vec
+#[derive(Debug)]
+pub(crate) enum HeaderStorage<'a> {
+    Borrowed(&'a mut [Header<'a>]),
+    Owned(Vec<Header<'a>>)
+}
+
+impl<'a> Default for HeaderStorage<'a> {
+    fn default() -> Self {
+        HeaderStorage::Borrowed(&mut [])
+    }
+}
+
+impl<'a> Deref for HeaderStorage<'a> {
+    type Target = [Header<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        match *self {
+            HeaderStorage::Borrowed(ref headers) => headers,
+            HeaderStorage::Owned(ref headers) => headers
+        }
+    }
+}
+
+impl<'a> DerefMut for HeaderStorage<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match *self {
+            HeaderStorage::Borrowed(ref mut headers) => headers,
+            HeaderStorage::Owned(ref mut headers) => headers
+        }
+    }
+}
+
+impl<'a> HeaderSlots<'a> for HeaderStorage<'a> {
+    fn store(&mut self, index: usize, header: Header<'a>) -> bool {
+        match *self {
+            HeaderStorage::Borrowed(ref mut headers) => headers.store(index, header),
+            HeaderStorage::Owned(ref mut headers) => headers.store(index, header)
+        }
+    }
+}
+
+// Counts the headers stored by a parse, since the entries after them may be left over from an
+// earlier one
+struct CountedSlots<'s, 'a> {
+    storage: &'s mut HeaderStorage<'a>,
+    count: usize
+}
+
+impl<'s, 'a> CountedSlots<'s, 'a> {
+    fn new(storage: &'s mut HeaderStorage<'a>) -> Self {
+        Self { storage, count: 0 }
+    }
+}
+
+impl<'s, 'a> HeaderSlots<'a> for CountedSlots<'s, 'a> {
+    fn store(&mut self, index: usize, header: Header<'a>) -> bool {
+
+        if !self.storage.store(index, header) {
+            return false;
+        }
+
+        self.count = index + 1;
+
+        true
+    }
+}
+
+/// A parsed request that owns its data, so it can be stored or moved across threads
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct OwnedRequest {
+
+    /// The request method, such as `GET`.
+    pub(crate) method: Vec<u8>,
+
+    /// The request path, such as `/events`.
+    pub(crate) path: Vec<u8>,
+
+    /// The request version, such as `1.1`.
+    pub(crate) version: Vec<u8>,
+
+    /// The request headers as name and value pairs, in the order they were received.
+    pub(crate) headers: Vec<(Vec<u8>, Vec<u8>)>,
+
+    /// The request body, such as `{\"dummy\": \"response\"}`
+    pub(crate) body: Vec<u8>
+
+}
+
+impl OwnedRequest {
+
+    pub fn method(&self) -> &[u8] {
+        &self.method
+    }
+
+    pub fn path(&self) -> &[u8] {
+        &self.path
+    }
+
+    pub fn version(&self) -> &[u8] {
+        &self.version
+    }
+
+    pub fn headers(&self) -> &[(Vec<u8>, Vec<u8>)] {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+// Compare the parsed message only, unused header slots are not part of it
+impl<'a, 'b> PartialEq<Request<'b>> for Request<'a> {
+    fn eq(&self, other: &Request<'b>) -> bool {
+        self.method == other.method
+            && self.path == other.path
+            && self.version == other.version
+            && self.headers() == other.headers()
+            && self.body == other.body
+    }
+}
+
+impl<'a> fmt::Display for Request<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        writeln!(f, "Request {{")?;
+        writeln!(f, "    method: {},", String::from_utf8_lossy(self.method))?;
+        writeln!(f, "    path: {},", String::from_utf8_lossy(self.path))?;
+        writeln!(f, "    version: HTTP/{},", String::from_utf8_lossy(self.version))?;
+        writeln!(f, "    headers: {{")?;
+
+        for header in self.headers() {
+            writeln!(f, "        {}: {}", String::from_utf8_lossy(header.name), String::from_utf8_lossy(header.value))?;
+        }
+
+        writeln!(f, "    }}")?;
+        write!(f, "}}")
     }
 }
 
@@ -1,7 +1,28 @@
-use std::str;
 use std::fmt;
-use std::ops::Add;
+use crate::http::etag::ETag;
+use crate::http::parse::body;
+use crate::http::parse::etag;
+use crate::http::parse::Stage;
+use crate::http::parse::Status;
+use crate::http::date::HttpDate;
 use crate::http::header::Header;
+use crate::http::auth::Challenge;
+use crate::http::body::BodyLength;
+use crate::http::parse::http_date;
+use crate::http::parse::ParserError;
+use crate::http::parse::status_line;
+use crate::http::range::ContentRange;
+use crate::http::status::StatusClass;
+use crate::http::body::ChunkedDecoder;
+use crate::http::config::ParserConfig;
+use crate::http::parse::cache_control;
+use crate::http::parse::content_range;
+use crate::http::cache::CacheDirective;
+use crate::http::parse::content_length;
+use crate::http::parse::headers_partial;
+use crate::http::parse::headers_iterator;
+use crate::http::parse::validate_version;
+use crate::http::parse::www_authenticate;
 
 #[derive(Debug, Default)]
 pub struct Response<'a> {
@@ -34,78 +55,343 @@ impl<'a> Response<'a> {
         self.status
     }
 
+    /// The category of the status code, such as `Success` for `200`.
+    pub fn status_class(&self) -> StatusClass {
+        StatusClass::from_status(self.status)
+    }
+
     pub fn reason(&self) -> &[u8] {
         self.reason
     }
 
-    pub fn headers(&self) -> Vec<Header> {
+    pub fn headers(&self) -> Vec<Header<'_>> {
         self.headers.to_owned()
     }
 
     pub fn body(&self) -> &[u8] {
         self.body
     }
-}
 
-impl<'a> fmt::Display for Response<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut headers = String::new();
+    /// Returns the range from the `Content-Range` header.
+    ///
+    /// `None` when the header is absent or malformed.
+    pub fn content_range(&self) -> Option<ContentRange> {
+        match content_range(self.header_bytes("Content-Range")?) {
+            Ok((_, range)) => Some(range),
+            Err(_) => None
+        }
+    }
 
-        let headers_length = self.headers().len();
+    /// Returns the directives of every `Cache-Control` header, in order.
+    ///
+    /// https://tools.ietf.org/html/rfc7234#section-5.2.2
+    pub fn cache_control(&self) -> Vec<CacheDirective<'_>> {
+        self.headers_all("Cache-Control").flat_map(cache_control).collect()
+    }
 
-        for (i, element) in self.headers().iter().enumerate() {
-            let header;
+    /// Returns the date from the `Date` header, when the response was generated.
+    ///
+    /// `None` when the header is absent or its date is malformed.
+    ///
+    /// https://tools.ietf.org/html/rfc7231#section-7.1.1.2
+    pub fn date(&self) -> Option<HttpDate> {
+        match http_date(self.header_bytes("Date")?) {
+            Ok((_, date)) => Some(date),
+            Err(_) => None
+        }
+    }
 
-            if i != (headers_length - 1) {
+    /// Returns the entity-tag from the `ETag` header.
+    ///
+    /// `None` when the header is absent or malformed.
+    ///
+    /// https://tools.ietf.org/html/rfc7232#section-2.3
+    pub fn etag(&self) -> Option<ETag<'_>> {
+        match etag(self.header_bytes("ETag")?) {
+            Ok((_, etag)) => Some(etag),
+            Err(_) => None
+        }
+    }
 
-                header = format!("{}: {}, ", str::from_utf8(element.name).unwrap(), str::from_utf8(element.value).unwrap());
+    /// Returns the challenges of every `WWW-Authenticate` header, in the order received.
+    ///
+    /// Malformed headers are skipped, the result is empty when there are none.
+    pub fn www_authenticate(&self) -> Vec<Challenge<'_>> {
+        self.headers_all("WWW-Authenticate")
+            .filter_map(|value| www_authenticate(value).ok())
+            .flat_map(|(_, challenges)| challenges)
+            .collect()
+    }
 
-            } else {
+    /// Determines how the body is framed, following the precedence of RFC 7230.
+    ///
+    /// `1xx`, `204` and `304` responses have no body whatever their headers say. Otherwise a
+    /// final `chunked` coding takes precedence over `Content-Length`, and without either the
+    /// body runs until the connection closes. A response to `HEAD` has no body either, which
+    /// only the request method can tell.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.3.3
+    pub fn body_length(&self) -> Result<BodyLength, ParserError> {
 
-                header = format!("{}: {}", str::from_utf8(element.name).unwrap(), str::from_utf8(element.value).unwrap());
+        let status = self.status;
 
-            }
+        if status / 100 == 1 || status == 204 || status == 304 {
+            return Ok(BodyLength::None);
+        }
+
+        let transfer_encoding = self.headers.iter().rev().find(|h| h.name_eq(b"Transfer-Encoding"));
+        let length_header = self.header_bytes("Content-Length");
+
+        let chunked = transfer_encoding.map(|h| match h.value.rsplit(|&b| b == b',').next() {
+            Some(coding) => coding.trim_ascii().eq_ignore_ascii_case(b"chunked"),
+            None => false
+        });
+
+        match (chunked, length_header) {
+            (Some(true), _) => Ok(BodyLength::Chunked),
+            (None, Some(value)) => Ok(BodyLength::Fixed(content_length(value)?)),
+            // Without a final chunked coding or a length, the body runs until the connection closes
+            _ => Ok(BodyLength::CloseDelimited)
+        }
+    }
+
+    /// Parses a response, reading its body as framed by its headers.
+    ///
+    /// A response to a `HEAD` request carries no body, even with a `Content-Length`, parse
+    /// those with `parse_for_method` instead.
+    pub fn parse(&mut self, input: &'a [u8]) -> Result<(), ParserError> {
+        self.parse_message(input, b"GET").map(|_| ())
+    }
+
+    /// Parses a response to a request made with `method`.
+    ///
+    /// When `method` is `HEAD` the declared `Content-Length` is ignored and the body left
+    /// empty, as it is for `1xx`, `204` and `304` responses to any method.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3.3.3
+    pub fn parse_for_method(&mut self, input: &'a [u8], method: &[u8]) -> Result<(), ParserError> {
+        self.parse_message(input, method).map(|_| ())
+    }
 
-            headers.push_str(&header)
+    /// Parses a response from a buffer that may not hold all of it yet.
+    ///
+    /// Returns `Partial` when the status line, headers or body are cut short. A body that runs
+    /// until the connection closes is never complete, parse it with `parse` once the connection
+    /// has closed. Calling it again with the buffer extended parses the response from the start.
+    pub fn parse_partial(&mut self, input: &'a [u8]) -> Result<Status, ParserError> {
+
+        // Status line
+        let (unparsed_input, (version, status, reason)) = match status_line(input) {
+            Ok(parsed) => parsed,
+            Err(_) if !input.contains(&b'\n') => return Ok(Status::Partial),
+            Err(e) => return Err(ParserError::syntax(Stage::StatusLine, e))
         };
 
-        write!(
-            f,
-            "Response {{ version: HTTP/{}, status: {}, reason: {}, headers: {{ {} }}, body: {{ {} }} }}",
-            str::from_utf8(self.version).unwrap(),
-            self.status,
-            str::from_utf8(self.reason).unwrap(),
-            headers,
-            str::from_utf8(self.body).unwrap()
-        )
+        validate_version(version)?;
+
+        self.version = version;
+        self.status = status;
+        self.reason = reason;
+        self.body = b"";
+
+        // Headers
+        self.headers.clear();
+
+        let unparsed_input = match headers_partial(unparsed_input, &mut self.headers, &ParserConfig::default())? {
+            Some(input) => input,
+            None => return Ok(Status::Partial)
+        };
+
+        // Content
+        let length = match self.body_length()? {
+            BodyLength::Fixed(length) if length > unparsed_input.len() => return Ok(Status::Partial),
+            BodyLength::Fixed(length) => length,
+            BodyLength::Chunked => {
+
+                let mut chunks = ChunkedDecoder::new(unparsed_input);
+
+                while let Some(data) = chunks.next() {
+                    if let Err(e) = data {
+                        return match chunks.needs_more_input() {
+                            true => Ok(Status::Partial),
+                            false => Err(e)
+                        };
+                    }
+                }
+
+                unparsed_input.len() - chunks.remaining().len()
+            },
+            BodyLength::CloseDelimited => return Ok(Status::Partial),
+            BodyLength::None => 0
+        };
+
+        self.body = &unparsed_input[..length];
+
+        Ok(Status::Complete(input.len() - unparsed_input.len() + length))
+    }
+
+    /// Parses every response in `input`, in order, such as the answers to pipelined requests.
+    ///
+    /// Parsing stops once the input is exhausted or what remains does not yet hold a complete
+    /// response. A response whose body runs until the connection closes is never complete, so
+    /// it is left in the input as well.
+    ///
+    /// Returns the complete responses along with the offset in `input` where parsing stopped,
+    /// where the next call should resume once more input is available, as `Request::parse_all`
+    /// does.
+    pub fn parse_all(input: &'a [u8]) -> Result<(Vec<Response<'a>>, usize), ParserError> {
+
+        let mut responses = Vec::new();
+        let mut offset = 0;
+
+        while offset < input.len() {
+
+            let mut response = Response::new();
+
+            match response.parse_partial(&input[offset..])? {
+                Status::Complete(length) => offset += length,
+                Status::Partial => break
+            }
+
+            responses.push(response);
+        }
+
+        Ok((responses, offset))
+    }
+
+    /// Serializes the response to its wire format, the status line, headers, empty line and body.
+    ///
+    /// Header values and the body are written as they are, so they may hold binary data.
+    ///
+    /// https://tools.ietf.org/html/rfc7230#section-3
+    pub fn to_bytes(&self) -> Vec<u8> {
+
+        let mut bytes = Vec::with_capacity(64 + self.body.len());
+
+        bytes.extend_from_slice(b"HTTP/");
+        bytes.extend_from_slice(self.version);
+        bytes.push(b' ');
+        bytes.extend_from_slice(self.status.to_string().as_bytes());
+        bytes.push(b' ');
+        bytes.extend_from_slice(self.reason);
+        bytes.extend_from_slice(b"\r\n");
+
+        for header in &self.headers {
+            bytes.extend_from_slice(header.name);
+            bytes.extend_from_slice(b": ");
+            bytes.extend_from_slice(header.value);
+            bytes.extend_from_slice(b"\r\n");
+        }
+
+        bytes.extend_from_slice(b"\r\n");
+        bytes.extend_from_slice(self.body);
+
+        bytes
+    }
+
+    /// Sets the header `name` to `value`, replacing every header with the same name, ignoring ASCII case.
+    pub fn set_header(&mut self, name: &'a [u8], value: &'a [u8]) {
+
+        self.headers.retain(|h| !h.name_eq(name));
+
+        self.add_header(name, value);
+    }
+
+    /// Appends the header `name: value`, keeping any header with the same name.
+    pub fn add_header(&mut self, name: &'a [u8], value: &'a [u8]) {
+        self.headers.push(Header { name, value });
+    }
+
+    /// The raw value of the first header whose name matches `name`, ignoring ASCII case, as
+    /// `Request::header_bytes` returns it.
+    fn header_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.headers.iter().find(|h| h.name_eq(name.as_bytes())).map(|h| h.value)
     }
-}
 
-impl Into<String> for Response<'_> {
-    fn into(self) -> String {
+    /// The value of every header whose name matches `name`, ignoring ASCII case, in order.
+    fn headers_all<'s>(&'s self, name: &'s str) -> impl Iterator<Item = &'s [u8]> + 's {
+        self.headers.iter().filter(move |h| h.name_eq(name.as_bytes())).map(|h| h.value)
+    }
+
+    /// Parses the status line, headers and body, returning the input that follows them.
+    fn parse_message(&mut self, input: &'a [u8], method: &[u8]) -> Result<&'a [u8], ParserError> {
 
-        let headers: String = self.headers()
-            .into_iter()
-            .map(|h| [str::from_utf8(h.name).unwrap(), str::from_utf8(h.value).unwrap()].join(": "))
-            .collect::<Vec<String>>()
-            .join("\r\n");
+        // Status line
+        let (unparsed_input, (version, status, reason)) = match status_line(input) {
+            Ok(parsed) => parsed,
+            Err(e) => return Err(ParserError::syntax(Stage::StatusLine, e))
+        };
 
-        let content = str::from_utf8(self.body).unwrap();
+        validate_version(version)?;
 
-        let mut result= "HTTP/".to_string();
+        self.version = version;
+        self.status = status;
+        self.reason = reason;
+        self.body = b"";
 
-        result = result.add(&[str::from_utf8(self.version).unwrap(), self.status.to_string().as_str(), str::from_utf8(self.reason).unwrap()].join(" "));
+        // Headers
+        self.headers.clear();
 
-        result = result.add("\r\n");
+        let unparsed_input = headers_iterator(unparsed_input, &mut self.headers, &ParserConfig::default())?;
 
-        if ! headers.is_empty() {
-            result = result.add(&headers).add("\r\n\r\n");
+        // Content
+        if method == b"HEAD" {
+            return Ok(unparsed_input);
         }
 
-        if ! content.is_empty() {
-            result = result.add(&content);
+        match self.body_length()? {
+            BodyLength::Chunked => {
+
+                let mut chunks = ChunkedDecoder::new(unparsed_input);
+
+                for data in chunks.by_ref() {
+                    data?;
+                }
+
+                self.body = &unparsed_input[..unparsed_input.len() - chunks.remaining().len()];
+
+                Ok(chunks.remaining())
+            },
+            BodyLength::Fixed(length) => match body(length, unparsed_input) {
+                Ok((input, body)) => {
+
+                    self.body = body;
+
+                    Ok(input)
+                },
+                Err(_) => Err(ParserError::IncompleteBody { expected: length, got: unparsed_input.len() })
+            },
+            BodyLength::CloseDelimited => {
+
+                self.body = unparsed_input;
+
+                Ok(&unparsed_input[unparsed_input.len()..])
+            },
+            BodyLength::None => Ok(unparsed_input)
         }
+    }
+}
+
+impl<'a> fmt::Display for Response<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Response {{")?;
+        writeln!(f, "    version: HTTP/{},", String::from_utf8_lossy(self.version))?;
+        writeln!(f, "    status: {},", self.status)?;
+        writeln!(f, "    reason: {},", String::from_utf8_lossy(self.reason))?;
+        writeln!(f, "    headers: {{")?;
+
+        for header in &self.headers {
+            writeln!(f, "        {}: {}", String::from_utf8_lossy(header.name), String::from_utf8_lossy(header.value))?;
+        }
+
+        writeln!(f, "    }},")?;
+        writeln!(f, "    body: {{ {} }}", String::from_utf8_lossy(self.body))?;
+        write!(f, "}}")
+    }
+}
 
-        result
+impl From<Response<'_>> for String {
+    fn from(response: Response<'_>) -> String {
+        String::from_utf8_lossy(&response.to_bytes()).into_owned()
     }
 }
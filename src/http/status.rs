@@ -0,0 +1,39 @@
+/// The category of a response status code, given by its first digit
+///
+/// https://tools.ietf.org/html/rfc7231#section-6
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum StatusClass {
+
+    /// `1xx`, the request was received and is being processed.
+    Informational,
+
+    /// `2xx`, the request was received, understood and accepted.
+    Success,
+
+    /// `3xx`, further action is needed to complete the request.
+    Redirection,
+
+    /// `4xx`, the request is malformed or cannot be fulfilled.
+    ClientError,
+
+    /// `5xx`, the server failed to fulfil a valid request.
+    ServerError,
+
+    /// Any status code outside `100..=599`.
+    Unknown
+}
+
+impl StatusClass {
+
+    /// The class of `status`, such as `ClientError` for `404`.
+    pub fn from_status(status: u16) -> Self {
+        match status / 100 {
+            1 => StatusClass::Informational,
+            2 => StatusClass::Success,
+            3 => StatusClass::Redirection,
+            4 => StatusClass::ClientError,
+            5 => StatusClass::ServerError,
+            _ => StatusClass::Unknown
+        }
+    }
+}
@@ -0,0 +1,51 @@
+/// The form of an HTTP request target, along with its components
+///
+/// https://tools.ietf.org/html/rfc7230#section-5.3
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RequestTarget<'a> {
+
+    /// An absolute path with an optional query, such as `/where?q=now`.
+    Origin {
+
+        /// The absolute path, such as `/where`.
+        path: &'a [u8],
+
+        /// The query without its leading `?`, such as `q=now`.
+        query: Option<&'a [u8]>
+    },
+
+    /// An absolute URI, such as `http://www.example.org/pub/WWW/TheProject.html`.
+    ///
+    /// Used when making a request to a proxy.
+    Absolute {
+
+        /// The scheme, such as `http`.
+        scheme: &'a [u8],
+
+        /// The authority, such as `www.example.org`, empty when the URI has none.
+        authority: &'a [u8],
+
+        /// The path, such as `/pub/WWW/TheProject.html`, possibly empty.
+        path: &'a [u8],
+
+        /// The query without its leading `?`.
+        query: Option<&'a [u8]>
+    },
+
+    /// The authority component of a URI, such as `www.example.com:80`.
+    ///
+    /// Only used with `CONNECT`.
+    Authority {
+
+        /// The host, such as `www.example.com`.
+        host: &'a [u8],
+
+        /// The port, such as `80`.
+        port: Option<u16>
+    },
+
+    /// A single asterisk, `*`.
+    ///
+    /// Only used with a server-wide `OPTIONS` request.
+    Asterisk
+}
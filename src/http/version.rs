@@ -0,0 +1,43 @@
+use crate::http::parse::ParserError;
+
+/// An HTTP protocol version, such as `1.1`
+///
+/// Fields are ordered so that comparing two versions compares them numerically.
+///
+/// https://tools.ietf.org/html/rfc7230#section-2.6
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct Version {
+
+    /// The major version, such as `1` for `HTTP/1.1`.
+    pub major: u8,
+
+    /// The minor version, such as `1` for `HTTP/1.1`.
+    pub minor: u8
+}
+
+impl Version {
+
+    /// `HTTP/1.0`
+    pub const HTTP_10: Version = Version { major: 1, minor: 0 };
+
+    /// `HTTP/1.1`
+    pub const HTTP_11: Version = Version { major: 1, minor: 1 };
+
+    /// `HTTP/2.0`
+    pub const HTTP_20: Version = Version { major: 2, minor: 0 };
+
+    /// Parses a version as it follows `HTTP/`, such as `1.1`, or `2` for `HTTP/2.0`.
+    ///
+    /// Each component must be a single digit, so `12.0` fails with `InvalidVersion` rather than
+    /// being accepted as an extension version.
+    pub fn from_bytes(input: &[u8]) -> Result<Version, ParserError> {
+
+        let digit = |b: u8| b.is_ascii_digit();
+
+        match *input {
+            [major, b'.', minor] if digit(major) && digit(minor) => Ok(Version { major: major - b'0', minor: minor - b'0' }),
+            [major @ b'2'] | [major @ b'3'] => Ok(Version { major: major - b'0', minor: 0 }),
+            _ => Err(ParserError::InvalidVersion)
+        }
+    }
+}
@@ -1,7 +1,32 @@
 use nom::Err;
 use parser::http;
+use std::borrow::Cow;
 use nom::error::ErrorKind;
+use parser::http::etag::ETag;
+use parser::http::parse::Stage;
+use parser::http::parse::Status;
+use parser::http::date::HttpDate;
+use parser::http::method::Method;
+use parser::http::parser::Parser;
+use parser::http::auth::Challenge;
+use parser::http::body::BodyLength;
+use parser::http::message::Message;
 use parser::http::request::Request;
+use parser::http::version::Version;
+use parser::http::auth::Credentials;
+use parser::http::etag::IfNoneMatch;
+use parser::http::media::ContentType;
+use parser::http::parse::ParserError;
+use parser::http::parser::ParseState;
+use parser::http::response::Response;
+use parser::http::header::HeaderMatch;
+use parser::http::range::ContentRange;
+use parser::http::status::StatusClass;
+use parser::http::body::ChunkedDecoder;
+use parser::http::config::ParserConfig;
+use parser::http::cache::CacheDirective;
+use parser::http::target::RequestTarget;
+use parser::http::disposition::ContentDisposition;
 
 #[test]
 fn test_method() {
@@ -80,6 +105,12 @@ fn test_request_without_body() {
     assert_eq!(request.path(), b"/");
     assert_eq!(request.version(), b"1.1");
     assert_eq!(request.headers().len(), 12);
+    assert_eq!(request.header_count(), 12);
+    assert_eq!(request.connection_tokens(), vec![&b"Upgrade"[..]]);
+    assert_eq!(
+        request.accept_language(),
+        vec![(&b"en-ZA"[..], 1.0), (&b"en-GB"[..], 0.9), (&b"en-US"[..], 0.8), (&b"en"[..], 0.7)]
+    );
     assert_eq!(request.body().len(), 0);
 }
 
@@ -159,3 +190,3627 @@ fn test_header() {
 
     assert_eq!((test_header.name(), test_header.value()), (&b"Host"[..], &b"127.0.0.1:9000"[..]))
 }
+
+#[test]
+fn test_request_str_accessors() {
+    let data = "\
+        GET /events?id=1 HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: Upgrade\r\n\
+        Upgrade: websocket\r\n\
+        Origin: http://local.test.tld\r\n\
+        Sec-WebSocket-Version: 13\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.method_str(), Ok("GET"));
+    assert_eq!(request.path_str(), Ok("/events?id=1"));
+    assert_eq!(request.version_str(), Ok("1.1"));
+}
+
+#[test]
+fn test_request_path_str_rejects_invalid_utf8() {
+    let data = b"GET /\xff HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert!(request.path_str().is_err());
+    assert_eq!(request.method_str(), Ok("GET"));
+}
+
+#[test]
+fn test_request_headers_all() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        X-Forwarded-For: 10.0.0.1\r\n\
+        Pragma: no-cache\r\n\
+        x-forwarded-for: 10.0.0.2, 10.0.0.3\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    let values: Vec<&[u8]> = request.headers_all("X-Forwarded-For").collect();
+
+    assert_eq!(values, vec![&b"10.0.0.1"[..], &b"10.0.0.2, 10.0.0.3"[..]]);
+    assert_eq!(request.header("X-FORWARDED-FOR").map(|h| h.value()), Some(&b"10.0.0.1"[..]));
+    assert_eq!(request.headers_all("Set-Cookie").count(), 0);
+}
+
+#[test]
+fn test_chunk() {
+    assert_eq!(http::parse::chunk(b"4\r\nWiki\r\n0\r\n\r\n"), Ok((&b"0\r\n\r\n"[..], &b"Wiki"[..])));
+    assert_eq!(http::parse::chunk(b"5;name=value\r\npedia\r\n"), Ok((&b""[..], &b"pedia"[..])));
+    assert_eq!(http::parse::chunk(b"0\r\n\r\n"), Ok((&b""[..], &b""[..])));
+    assert_eq!(http::parse::chunk(b"0\r\nX-Checksum: 1\r\n\r\n"), Ok((&b""[..], &b""[..])));
+    assert_eq!(http::parse::trailer_part(b"X-Checksum: 1\r\n\r\n"), Ok((&b"\r\n"[..], &b"X-Checksum: 1\r\n"[..])));
+    assert_eq!(http::parse::chunk_size(b"1A\r\n"), Ok((&b"\r\n"[..], 26)));
+}
+
+#[test]
+fn test_chunk_extensions() {
+    assert_eq!(http::parse::chunk_size_line(b"5;name=value;flag\r\n"), Ok((&b""[..], 5)));
+    assert_eq!(http::parse::chunk_size_line(b"5 ; name = \"a;b\"\r\n"), Ok((&b""[..], 5)));
+
+    // Bytes after a valid size that do not start an extension are rejected
+    for line in [&b"1g\r\n"[..], b"5 xyz\r\n", b"5;\r\n", b"5;=value\r\n"].iter() {
+        assert!(http::parse::chunk_size_line(line).is_err(), "{:?}", String::from_utf8_lossy(line));
+    }
+
+    let chunks = [
+        ("1g\r\nh\r\n0\r\n\r\n", Err(ParserError::Body)),
+        ("5 xyz\r\nhello\r\n0\r\n\r\n", Err(ParserError::Body)),
+        ("ffffffffffffffff\r\nhello\r\n0\r\n\r\n", Err(ParserError::ChunkSizeOverflow)),
+    ];
+
+    let mut config = ParserConfig::new();
+    config.max_body_bytes = usize::MAX;
+
+    for (body, result) in chunks.iter() {
+
+        let data = format!("POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\n{}", body);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        assert_eq!(request.parse_with_config(data.as_bytes(), &config), *result, "{:?}", body);
+        assert_eq!(request.parse_partial_with_config(data.as_bytes(), &config).map(|_| ()), *result, "{:?}", body);
+
+        // Cut short right after the size line, the malformed line is still reported
+        let cut = data.len() - body.len() + body.find('\n').unwrap() + 1;
+
+        assert_eq!(request.parse_partial_with_config(&data.as_bytes()[..cut], &config).map(|_| ()), *result, "{:?}", body);
+    }
+}
+
+#[test]
+fn test_request_parse_streaming_content_length() {
+    let data = "\
+        POST /upload HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 16\r\n\
+        Content-Type: application/json\r\n\
+        \r\n\
+        {\"test\": \"data\"}\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    let mut chunks: Vec<Vec<u8>> = Vec::new();
+
+    match request.parse_streaming(data.as_bytes(), |chunk| chunks.push(chunk.to_vec())) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(chunks, vec![b"{\"test\": \"data\"}".to_vec()]);
+    assert_eq!(request.body().len(), 0);
+}
+
+#[test]
+fn test_request_parse_streaming_chunked() {
+    let data = "\
+        POST /upload HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        4\r\n\
+        Wiki\r\n\
+        5\r\n\
+        pedia\r\n\
+        E\r\n \
+        in\r\n\r\nchunks.\r\n\
+        0\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    let mut received = Vec::new();
+    let mut count = 0;
+
+    match request.parse_streaming(data.as_bytes(), |chunk| {
+        received.extend_from_slice(chunk);
+        count += 1;
+    }) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(count, 3);
+    assert_eq!(received, b"Wikipedia in\r\n\r\nchunks.".to_vec());
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_request_decoded_body_gzip() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"{\"test\": \"data\"}").unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut data = format!("\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Encoding: gzip\r\n\
+        Content-Length: {}\r\n\
+        \r\n\
+    ", compressed.len()).into_bytes();
+    data.extend_from_slice(&compressed);
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(&data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.body(), &compressed[..]);
+    assert_eq!(request.decoded_body(), Ok(b"{\"test\": \"data\"}".to_vec()));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_request_decoded_body_chunked_gzip() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"{\"test\": \"data\"}").unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut data = b"\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Encoding: gzip\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+    ".to_vec();
+
+    // The compressed body is split over two chunks
+    for part in compressed.chunks(compressed.len() / 2 + 1) {
+        data.extend_from_slice(format!("{:x}\r\n", part.len()).as_bytes());
+        data.extend_from_slice(part);
+        data.extend_from_slice(b"\r\n");
+    }
+
+    data.extend_from_slice(b"0\r\n\r\n");
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(&data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_ne!(request.body(), &compressed[..]);
+    assert_eq!(request.decoded_body(), Ok(b"{\"test\": \"data\"}".to_vec()));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_request_decoded_body_without_encoding() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 4\r\n\
+        \r\n\
+        data\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.decoded_body(), Ok(b"data".to_vec()));
+}
+
+#[test]
+fn test_request_target_form() {
+    let requests: [(&[u8], RequestTarget); 4] = [
+        (b"OPTIONS * HTTP/1.1\r\nHost: www.example.org\r\n", RequestTarget::Asterisk),
+        (
+            b"GET /where?q=now HTTP/1.1\r\nHost: www.example.org\r\n",
+            RequestTarget::Origin { path: b"/where", query: Some(b"q=now") }
+        ),
+        (
+            b"GET http://www.example.org/index.html HTTP/1.1\r\nHost: www.example.org\r\n",
+            RequestTarget::Absolute { scheme: b"http", authority: b"www.example.org", path: b"/index.html", query: None }
+        ),
+        (
+            b"CONNECT www.example.com:80 HTTP/1.1\r\nHost: www.example.com:80\r\n",
+            RequestTarget::Authority { host: b"www.example.com", port: Some(80) }
+        ),
+    ];
+
+    for (data, form) in requests.iter() {
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        match request.parse(data) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(request.target_form(), Some(*form));
+    }
+}
+
+#[test]
+fn test_header_rejects_invalid_value_characters() {
+    let mut test_header = http::header::EMPTY_HEADER;
+
+    assert_eq!(
+        http::parse::header(b"X-Test: abc\0def\r\n", &mut test_header),
+        Err(Err::Failure((&b"\0def\r\n"[..], ErrorKind::Verify)))
+    );
+    assert_eq!(
+        http::parse::header(b"X-Test: \x0babc\r\n", &mut test_header),
+        Err(Err::Failure((&b"\x0babc\r\n"[..], ErrorKind::Verify)))
+    );
+    assert_eq!(test_header, http::header::EMPTY_HEADER);
+
+    let (_, _) = http::parse::header(b"X-Test: a\tb c\r\n", &mut test_header).unwrap();
+
+    assert_eq!(test_header.value(), &b"a\tb c"[..]);
+}
+
+#[test]
+fn test_request_with_invalid_header_value() {
+    let data = b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nX-Test: abc\0def\r\n\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data), Err(ParserError::InvalidHeaderValue));
+}
+
+#[test]
+fn test_request_header_value_obs_text() {
+    let data = b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nX-Name: Jos\xc3\xa9\r\n\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.header("X-Name").map(|h| h.value()), Some(&b"Jos\xc3\xa9"[..]));
+
+    let data = b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nX-Name: Jos\x01\r\n\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data), Err(ParserError::InvalidHeaderValue));
+}
+
+#[test]
+fn test_header_with_empty_value() {
+    let mut test_header = http::header::EMPTY_HEADER;
+
+    let (rest, _) = http::parse::header(b"X-Empty:\r\nHost: 127.0.0.1:9000\r\n", &mut test_header).unwrap();
+
+    assert_eq!((test_header.name(), test_header.value()), (&b"X-Empty"[..], &b""[..]));
+    assert_eq!(rest, &b"Host: 127.0.0.1:9000\r\n"[..]);
+
+    let mut test_header = http::header::EMPTY_HEADER;
+
+    let (rest, _) = http::parse::header(b"X-Empty: \r\n", &mut test_header).unwrap();
+
+    assert_eq!((test_header.name(), test_header.value()), (&b"X-Empty"[..], &b""[..]));
+    assert_eq!(rest, &b""[..]);
+}
+
+#[test]
+fn test_request_content_length() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        content-length: 16\r\n\
+        \r\n\
+        {\"test\": \"data\"}\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.content_length(), Some(Ok(16)));
+
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.content_length(), None);
+}
+
+#[test]
+fn test_request_malformed_content_length() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: abc\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data.as_bytes()), Err(ParserError::ContentLength));
+    assert_eq!(request.content_length(), Some(Err(ParserError::ContentLength)));
+}
+
+#[test]
+fn test_request_is_chunked() {
+    let encodings: [(&str, bool); 4] = [
+        ("chunked", true),
+        ("gzip, chunked", true),
+        ("chunked, gzip", false),
+        ("Chunked", true),
+    ];
+
+    for (encoding, chunked) in encodings.iter() {
+
+        let data = format!("POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: {}\r\n\r\n0\r\n\r\n", encoding);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        // A request whose final coding is not chunked has no reliable length
+        let expected = if *chunked { Ok(()) } else { Err(ParserError::TransferEncoding) };
+
+        assert_eq!(request.parse(data.as_bytes()), expected, "Transfer-Encoding: {}", encoding);
+        assert_eq!(request.is_chunked(), *chunked, "Transfer-Encoding: {}", encoding);
+    }
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert!(!request.is_chunked());
+}
+
+#[test]
+fn test_authorization() {
+    assert_eq!(
+        http::parse::authorization(b"Basic dXNlcjpwYXNz"),
+        Ok((&b""[..], Credentials::Basic { user: b"user".to_vec(), pass: b"pass".to_vec() }))
+    );
+    assert_eq!(
+        http::parse::authorization(b"Bearer abc.def.ghi"),
+        Ok((&b""[..], Credentials::Bearer(&b"abc.def.ghi"[..])))
+    );
+    assert_eq!(
+        http::parse::authorization(b"Digest username=\"user\", realm=\"test\""),
+        Ok((&b""[..], Credentials::Other { scheme: &b"Digest"[..], token: &b"username=\"user\", realm=\"test\""[..] }))
+    );
+    assert!(http::parse::authorization(b"Basic !!!").is_err());
+    assert_eq!(http::parse::basic_credentials(b"!!!"), Err(ParserError::InvalidAuthorization));
+    assert_eq!(http::parse::basic_credentials(b"dXNlcg=="), Err(ParserError::InvalidAuthorization));
+}
+
+#[test]
+fn test_request_authorization() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Authorization: Bearer abc.def.ghi\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.authorization(), Some(Credentials::Bearer(&b"abc.def.ghi"[..])));
+}
+
+#[test]
+fn test_host() {
+    assert_eq!(http::parse::host(b"127.0.0.1:9000"), Ok((&b""[..], (&b"127.0.0.1"[..], Some(9000)))));
+    assert_eq!(http::parse::host(b"[::1]:8080"), Ok((&b""[..], (&b"[::1]"[..], Some(8080)))));
+    assert_eq!(http::parse::host(b"[::1]"), Ok((&b""[..], (&b"[::1]"[..], None))));
+    assert_eq!(http::parse::host(b"example.com"), Ok((&b""[..], (&b"example.com"[..], None))));
+}
+
+#[test]
+fn test_request_host() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: Upgrade\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.host(), Some((&b"127.0.0.1"[..], Some(9000))));
+}
+
+#[test]
+fn test_request_host_invalid_port() {
+    for host in ["example.com:99999", "example.com:abc", "example.com:"].iter() {
+
+        let data = format!("GET / HTTP/1.1\r\nHost: {}\r\n\r\n", host);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        match request.parse(data.as_bytes()) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(request.host(), None, "{:?}", host);
+    }
+}
+
+#[test]
+fn test_request_target_too_long() {
+    let mut config = ParserConfig::new();
+    config.max_request_target_bytes = 16;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(
+        request.parse_with_config(b"GET /a/very/long/request/target HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n", &config),
+        Err(ParserError::RequestTargetTooLong)
+    );
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_with_config(b"GET /short HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n", &config), Ok(()));
+
+    let path = "/".repeat(ParserConfig::default().max_request_target_bytes + 1);
+    let data = format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n", path);
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data.as_bytes()), Err(ParserError::RequestTargetTooLong));
+}
+
+#[test]
+fn test_request_header_too_large() {
+    let mut config = ParserConfig::new();
+    config.max_header_bytes = 32;
+
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        User-Agent: Mozilla/5.0 (Windows NT 10.0; Win64; x64)\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_with_config(data.as_bytes(), &config), Err(ParserError::HeaderTooLarge));
+}
+
+#[test]
+fn test_request_headers_block_too_large() {
+    let mut config = ParserConfig::new();
+    config.max_headers_block_bytes = 64;
+
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Pragma: no-cache\r\n\
+        Cache-Control: no-cache\r\n\
+        Origin: http://local.test.tld\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_with_config(data.as_bytes(), &config), Err(ParserError::HeadersBlockTooLarge));
+
+    config.max_headers_block_bytes = 128;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_with_config(data.as_bytes(), &config), Ok(()));
+}
+
+#[test]
+fn test_request_reset() {
+    let first = "\
+        POST /first HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Pragma: no-cache\r\n\
+        Cache-Control: no-cache\r\n\
+        Content-Length: 4\r\n\
+        \r\n\
+        data\
+    ";
+
+    let second = "\
+        GET /second HTTP/1.0\r\n\
+        Host: 127.0.0.1:9000\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(first.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.headers().len(), 4);
+    assert_eq!(request.body(), b"data");
+
+    request.reset();
+
+    assert_eq!(request.method(), b"");
+    assert_eq!(request.headers().len(), 0);
+
+    match request.parse(second.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.method(), b"GET");
+    assert_eq!(request.path(), b"/second");
+    assert_eq!(request.version(), b"1.0");
+    assert_eq!(request.headers().len(), 1);
+    assert_eq!(request.body().len(), 0);
+}
+
+#[test]
+fn test_request_to_owned() {
+    let owned = {
+        let data = b"\
+            POST /upload HTTP/1.1\r\n\
+            Host: 127.0.0.1:9000\r\n\
+            Content-Length: 4\r\n\
+            \r\n\
+            data\
+        ".to_vec();
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        match request.parse(&data) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        request.to_owned()
+    };
+
+    assert_eq!(owned.method(), b"POST");
+    assert_eq!(owned.path(), b"/upload");
+    assert_eq!(owned.version(), b"1.1");
+    assert_eq!(owned.headers(), &[
+        (b"Host".to_vec(), b"127.0.0.1:9000".to_vec()),
+        (b"Content-Length".to_vec(), b"4".to_vec()),
+    ]);
+    assert_eq!(owned.body(), b"data");
+
+    let handle = std::thread::spawn(move || owned.path().to_vec());
+
+    assert_eq!(handle.join().unwrap(), b"/upload".to_vec());
+}
+
+#[test]
+fn test_request_forwarded_for() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        X-Forwarded-For: 1.1.1.1, 2.2.2.2\r\n\
+        X-Forwarded-For: 3.3.3.3\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.forwarded_for(), vec![&b"1.1.1.1"[..], &b"2.2.2.2"[..], &b"3.3.3.3"[..]]);
+}
+
+#[test]
+fn test_forwarded() {
+    assert_eq!(
+        http::parse::forwarded(b"for=192.0.2.60;proto=http;by=203.0.113.43, for=\"[2001:db8:cafe::17]:4711\""),
+        vec![
+            vec![(&b"for"[..], &b"192.0.2.60"[..]), (&b"proto"[..], &b"http"[..]), (&b"by"[..], &b"203.0.113.43"[..])],
+            vec![(&b"for"[..], &b"[2001:db8:cafe::17]:4711"[..])],
+        ]
+    );
+}
+
+#[test]
+fn test_request_raw_headers() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Via: 1.0 fred\r\n\
+        Accept: text/html\r\n\
+        Via: 1.1 p.example.net\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    let raw: Vec<(&[u8], &[u8])> = request.raw_headers().map(|h| (h.name(), h.value())).collect();
+
+    assert_eq!(raw, vec![
+        (&b"Host"[..], &b"127.0.0.1:9000"[..]),
+        (&b"Via"[..], &b"1.0 fred"[..]),
+        (&b"Accept"[..], &b"text/html"[..]),
+        (&b"Via"[..], &b"1.1 p.example.net"[..]),
+    ]);
+    assert_eq!(request.header("Via").map(|h| h.value()), Some(&b"1.0 fred"[..]));
+}
+
+#[test]
+fn test_request_line_with_bare_lf() {
+    assert_eq!(
+        http::parse::request_line(b"GET / HTTP/1.1\nHost: 127.0.0.1:9000\r\n"),
+        Ok((&b"Host: 127.0.0.1:9000\r\n"[..], (&b"GET"[..], &b"/"[..], &b"1.1"[..], &b"\n"[..])))
+    );
+
+    let data = "\
+        GET / HTTP/1.1\n\
+        Host: 127.0.0.1:9000\r\n\
+        Pragma: no-cache\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.method(), b"GET");
+    assert_eq!(request.version(), b"1.1");
+    assert_eq!(request.headers().len(), 2);
+}
+
+#[test]
+fn test_header_name_eq() {
+    let mut test_header = http::header::EMPTY_HEADER;
+
+    let (_, _) = http::parse::header(b"Content-Length: 16\r\n", &mut test_header).unwrap();
+
+    assert!(test_header.name_eq(b"content-length"));
+    assert!(test_header.name_eq(b"Content-Length"));
+    assert!(test_header.name_eq(b"CONTENT-LENGTH"));
+    assert!(!test_header.name_eq(b"Content-Type"));
+}
+
+#[test]
+fn test_request_with_lowercase_content_length() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        content-length: 16\r\n\
+        \r\n\
+        {\"test\": \"data\"}\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.body(), b"{\"test\": \"data\"}");
+}
+
+#[test]
+fn test_request_parse_remaining() {
+    let second = "\
+        GET /second HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+    ";
+
+    let data = format!("\
+        POST /first HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 16\r\n\
+        \r\n\
+        {{\"test\": \"data\"}}\
+        {}\
+    ", second);
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    let remaining = match request.parse_remaining(data.as_bytes()) {
+        Ok(remaining) => remaining,
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    };
+
+    assert_eq!(request.path(), b"/first");
+    assert_eq!(request.body(), b"{\"test\": \"data\"}");
+    assert_eq!(remaining, second.as_bytes());
+}
+
+#[test]
+fn test_http09_simple_request() {
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(b"GET /index.html\r\n"), Err(ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::Tag }));
+
+    let mut config = ParserConfig::new();
+    config.allow_http09 = true;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse_with_config(b"GET /index.html\r\n", &config) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.method(), b"GET");
+    assert_eq!(request.path(), b"/index.html");
+    assert_eq!(request.version(), b"0.9");
+    assert_eq!(request.headers().len(), 0);
+    assert_eq!(request.body().len(), 0);
+}
+
+#[test]
+fn test_http09_simple_request_reuse() {
+    let mut config = ParserConfig::new();
+    config.allow_http09 = true;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    let data = "POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\n0\r\nChecksum: 0\r\n\r\n";
+
+    match request.parse_with_config(data.as_bytes(), &config) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.headers().len(), 2);
+    assert_eq!(request.trailers().len(), 1);
+
+    // Nothing from the previous request is left behind
+    match request.parse_with_config(b"GET /index.html\r\n", &config) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.version(), b"0.9");
+    assert_eq!(request.headers().len(), 0);
+    assert_eq!(request.header("Host"), None);
+    assert!(request.trailers().is_empty());
+    assert_eq!(request.raw(), b"GET /index.html\r\n");
+}
+
+#[test]
+fn test_http_date() {
+    let date = HttpDate { year: 1994, month: 11, day: 6, hour: 8, minute: 49, second: 37 };
+
+    assert_eq!(http::parse::http_date(b"Sun, 06 Nov 1994 08:49:37 GMT"), Ok((&b""[..], date)));
+    assert_eq!(http::parse::http_date(b"Sunday, 06-Nov-94 08:49:37 GMT"), Ok((&b""[..], date)));
+    assert_eq!(http::parse::http_date(b"Sun Nov  6 08:49:37 1994"), Ok((&b""[..], date)));
+    assert!(http::parse::http_date(b"Sun, 06 Foo 1994 08:49:37 GMT").is_err());
+    assert!(http::parse::http_date(b"Sun, 06 Nov 1994 25:49:37 GMT").is_err());
+    assert!(http::parse::http_date(b"yesterday").is_err());
+}
+
+#[test]
+fn test_request_conditional_dates() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        If-Modified-Since: Sat, 29 Oct 1994 19:43:31 GMT\r\n\
+        If-Unmodified-Since: not a date\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(
+        request.if_modified_since(),
+        Some(HttpDate { year: 1994, month: 10, day: 29, hour: 19, minute: 43, second: 31 })
+    );
+    assert_eq!(request.if_unmodified_since(), None);
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.if_modified_since(), None);
+    assert_eq!(request.if_unmodified_since(), None);
+}
+
+#[test]
+fn test_token() {
+    assert_eq!(http::parse::token(b"GET /x HTTP/1.1"), Ok((&b" /x HTTP/1.1"[..], &b"GET"[..])));
+    assert_eq!(http::parse::token(b"X-Custom_Header!#$%&'*+.^`|~: v"), Ok((&b": v"[..], &b"X-Custom_Header!#$%&'*+.^`|~"[..])));
+    assert_eq!(http::parse::token(b"gzip, chunked"), Ok((&b", chunked"[..], &b"gzip"[..])));
+    assert_eq!(http::parse::token(b""), Err(Err::Error((&b""[..], ErrorKind::TakeWhile1))));
+    assert_eq!(http::parse::token(b"(comment)"), Err(Err::Error((&b"(comment)"[..], ErrorKind::TakeWhile1))));
+    assert_eq!(http::parse::token(b"\"quoted\""), Err(Err::Error((&b"\"quoted\""[..], ErrorKind::TakeWhile1))));
+}
+
+#[test]
+fn test_extension_method() {
+    assert_eq!(http::parse::method(b"PROPFIND /x HTTP/1.1\r\n"), Ok((&b" /x HTTP/1.1\r\n"[..], &b"PROPFIND"[..])));
+    assert_eq!(http::parse::method(b"M-SEARCH * HTTP/1.1\r\n"), Ok((&b" * HTTP/1.1\r\n"[..], &b"M-SEARCH"[..])));
+}
+
+#[test]
+fn test_request_parse_all() {
+    let data = "\
+        GET /first HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Accept: text/html\r\n\
+        \r\n\
+        GET /second HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        \r\n\
+        GET /third HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Pragma: no-cache\r\n\
+        Cache-Control: no-cache\r\n\
+        \r\n\
+    ";
+
+    let mut arena = [http::header::EMPTY_HEADER; 32];
+
+    let (requests, offset) = match Request::parse_all(data.as_bytes(), &mut arena) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    };
+
+    assert_eq!(offset, data.len());
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests.iter().map(|r| r.path()).collect::<Vec<_>>(), vec![&b"/first"[..], &b"/second"[..], &b"/third"[..]]);
+    assert_eq!(requests.iter().map(|r| r.headers().len()).collect::<Vec<_>>(), vec![2, 1, 3]);
+    assert_eq!(requests[2].header("Pragma").map(|h| h.value()), Some(&b"no-cache"[..]));
+}
+
+#[test]
+fn test_request_parse_all_leaves_partial_request_line() {
+    let data = "\
+        POST /first HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 4\r\n\
+        \r\n\
+        dataGET /sec\
+    ";
+
+    let mut arena = [http::header::EMPTY_HEADER; 32];
+
+    let (requests, offset) = match Request::parse_all(data.as_bytes(), &mut arena) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    };
+
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].body(), b"data");
+    assert_eq!(&data[offset..], "GET /sec");
+}
+
+#[test]
+fn test_request_parse_all_leaves_partial_request() {
+    let first = "\
+        POST /first HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 4\r\n\
+        \r\n\
+        data\
+    ";
+
+    let trailing = [
+        "POST /second HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nContent-Length: 10\r\n\r\nhalf",
+        "GET /second HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nAccept: text/html\r\n",
+    ];
+
+    // A trailing request cut short in its body or headers is left for the next call
+    for partial in trailing.iter() {
+
+        let data = format!("{}{}", first, partial);
+
+        let mut arena = [http::header::EMPTY_HEADER; 32];
+
+        let (requests, offset) = match Request::parse_all(data.as_bytes(), &mut arena) {
+            Ok(parsed) => parsed,
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        };
+
+        assert_eq!(requests.len(), 1, "{:?}", partial);
+        assert_eq!(requests[0].path(), b"/first");
+        assert_eq!(offset, first.len());
+    }
+}
+
+#[test]
+fn test_request_headers_fill_array() {
+    let mut headers = [http::header::EMPTY_HEADER; 2];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nAccept: text/html\r\n") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.headers().len(), 2);
+}
+
+#[test]
+fn test_request_query() {
+    let cases = [
+        ("/a?b=1", "/a", Some("b=1")),
+        ("/a", "/a", None),
+        ("/a?", "/a", Some("")),
+        ("/a?b=1?c=2", "/a", Some("b=1?c=2")),
+    ];
+
+    for (path, path_only, query) in cases.iter() {
+
+        let data = format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n", path);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        match request.parse(data.as_bytes()) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(request.path_only(), path_only.as_bytes());
+        assert_eq!(request.query(), query.map(str::as_bytes));
+    }
+}
+
+#[test]
+fn test_request_conflicting_framing() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 5\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+        0\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data.as_bytes()), Err(ParserError::ConflictingFraming));
+}
+
+#[test]
+fn test_request_duplicate_content_length() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 5\r\n\
+        Content-Length: 5\r\n\
+        \r\n\
+        hello\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.body(), b"hello");
+}
+
+#[test]
+fn test_request_conflicting_content_length() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 5\r\n\
+        Content-Length: 6\r\n\
+        \r\n\
+        hello!\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data.as_bytes()), Err(ParserError::ConflictingContentLength));
+}
+
+#[test]
+fn test_header_canonical_name() {
+    let cases: [(&[u8], &[u8]); 3] = [
+        (b"CONTENT-TYPE: text/plain\r\n", b"Content-Type"),
+        (b"etag: \"abc\"\r\n", b"ETag"),
+        (b"x-custom: 1\r\n", b"x-custom"),
+    ];
+
+    for (data, canonical_name) in cases.iter() {
+
+        let mut test_header = http::header::EMPTY_HEADER;
+
+        let (_, _) = http::parse::header(data, &mut test_header).unwrap();
+
+        assert_eq!(&test_header.canonical_name()[..], *canonical_name);
+    }
+}
+
+#[test]
+fn test_request_headers_mut() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: keep-alive\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.headers_mut().len(), 2);
+
+    if let Some(header) = request.headers_mut().iter_mut().find(|h| h.name_eq(b"Connection")) {
+        header.value = b"close";
+    }
+
+    assert_eq!(request.header("Connection").map(|h| h.value()), Some(&b"close"[..]));
+    assert_eq!(request.headers().len(), 2);
+}
+
+#[test]
+fn test_query_pairs() {
+    assert_eq!(
+        http::parse::query_pairs(b"a=1&&b=&c"),
+        vec![(&b"a"[..], &b"1"[..]), (&b"b"[..], &b""[..]), (&b"c"[..], &b""[..])]
+    );
+}
+
+#[test]
+fn test_percent_decode() {
+    assert_eq!(http::parse::percent_decode(b"a%2Fb%3d"), Ok(b"a/b=".to_vec()));
+    assert_eq!(http::parse::percent_decode(b"a%2"), Err(ParserError::InvalidPercentEncoding));
+    assert_eq!(http::parse::percent_decode(b"a%zz"), Err(ParserError::InvalidPercentEncoding));
+    assert_eq!(http::parse::percent_decode(b"a%+1"), Err(ParserError::InvalidPercentEncoding));
+}
+
+#[test]
+fn test_request_query_pairs_decoded() {
+    let data = "\
+        GET /wallpapers/hd.png?v=hOlmDALJCWWdjzfBV4ZxJPmrdCLWB%2Ftq7Z%2Ffp4Q%2FxXbVPPREuMJMVGzKraTuhhNWxCCwi6yFEZg%3D&r=783333388&q=a+b%2Bc HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(
+        request.query_pairs_decoded(),
+        Ok(vec![
+            ("v".to_string(), "hOlmDALJCWWdjzfBV4ZxJPmrdCLWB/tq7Z/fp4Q/xXbVPPREuMJMVGzKraTuhhNWxCCwi6yFEZg=".to_string()),
+            ("r".to_string(), "783333388".to_string()),
+            ("q".to_string(), "a b+c".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_request_query_pairs_decoded_malformed() {
+    let data = "\
+        GET /?a=%4 HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.query_pairs_decoded(), Err(ParserError::InvalidPercentEncoding));
+}
+
+#[test]
+fn test_version_ordering() {
+    assert!(Version::HTTP_10 < Version::HTTP_11);
+    assert!(Version::HTTP_11 < Version::HTTP_20);
+    assert!(Version { major: 1, minor: 1 } > Version { major: 1, minor: 0 });
+    assert_eq!(Version::HTTP_11, Version { major: 1, minor: 1 });
+
+    let mut versions = vec![Version::HTTP_20, Version::HTTP_10, Version::HTTP_11];
+
+    versions.sort();
+
+    assert_eq!(versions, vec![Version::HTTP_10, Version::HTTP_11, Version::HTTP_20]);
+}
+
+#[test]
+fn test_response_set_header() {
+    let mut response = Response::new();
+
+    response.add_header(b"Content-Type", b"text/plain");
+    response.add_header(b"Set-Cookie", b"a=1");
+
+    response.set_header(b"content-type", b"application/json");
+
+    assert_eq!(response.headers().len(), 2);
+    assert_eq!(response.headers()[0].name(), b"Set-Cookie");
+    assert_eq!(response.headers()[1].name(), b"content-type");
+    assert_eq!(response.headers()[1].value(), b"application/json");
+}
+
+#[test]
+fn test_response_add_header() {
+    let mut response = Response::new();
+
+    response.add_header(b"Set-Cookie", b"a=1");
+    response.add_header(b"Set-Cookie", b"b=2");
+
+    assert_eq!(response.headers().len(), 2);
+    assert_eq!(response.headers()[0].value(), b"a=1");
+    assert_eq!(response.headers()[1].value(), b"b=2");
+}
+
+#[test]
+fn test_request_display() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: Upgrade\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(
+        request.to_string(),
+        "\
+Request {
+    method: GET,
+    path: /,
+    version: HTTP/1.1,
+    headers: {
+        Host: 127.0.0.1:9000
+        Connection: Upgrade
+    }
+}"
+    );
+}
+
+#[test]
+fn test_response_display() {
+    let mut response = Response::new();
+
+    response.version = b"1.1";
+    response.status = 200;
+    response.reason = b"OK";
+    response.body = b"hello";
+
+    response.add_header(b"Content-Type", b"text/plain");
+    response.add_header(b"Content-Length", b"5");
+
+    assert_eq!(
+        response.to_string(),
+        "\
+Response {
+    version: HTTP/1.1,
+    status: 200,
+    reason: OK,
+    headers: {
+        Content-Type: text/plain
+        Content-Length: 5
+    },
+    body: { hello }
+}"
+    );
+
+    assert_eq!(
+        String::from(response),
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nhello"
+    );
+}
+
+#[test]
+fn test_response_formatting_with_invalid_utf8() {
+    let mut response = Response::new();
+
+    response.version = b"1.1";
+    response.status = 200;
+    response.reason = b"OK";
+
+    response.add_header(b"X-Custom", b"caf\xff");
+
+    assert!(response.to_string().contains("X-Custom: caf\u{FFFD}"));
+    assert_eq!(String::from(response), "HTTP/1.1 200 OK\r\nX-Custom: caf\u{FFFD}\r\n\r\n");
+}
+
+#[test]
+fn test_request_formatting_with_invalid_utf8() {
+    let data = b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nX-Custom: caf\xff\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert!(request.to_string().contains("X-Custom: caf\u{FFFD}"));
+}
+
+#[test]
+fn test_request_raw() {
+    let data = "\
+        POST /upload HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 5\r\n\
+        \r\n\
+        hello\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.raw(), data.as_bytes());
+
+    // Input past the request, such as the next pipelined request, is not part of it
+    let pipelined = format!("{}GET /next HTTP/1.1\r\n\r\n", data);
+
+    assert_eq!(request.parse_remaining(pipelined.as_bytes()), Ok(&b"GET /next HTTP/1.1\r\n\r\n"[..]));
+    assert_eq!(request.raw(), data.as_bytes());
+
+    assert_eq!(request.parse_headers_only(data.as_bytes()), Ok(data.len() - 5));
+    assert_eq!(request.raw(), &data.as_bytes()[..data.len() - 5]);
+
+    assert!(request.parse(&data.as_bytes()[..data.len() - 1]).is_err());
+    assert_eq!(request.raw(), b"");
+}
+
+#[test]
+fn test_request_as_str_lossy() {
+    let data = b"POST /upload HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nX-Custom: caf\xff\r\nContent-Length: 3\r\n\r\nab\xfe";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(
+        request.as_str_lossy(),
+        "POST /upload HTTP/1.1\nHost: 127.0.0.1:9000\nX-Custom: caf\u{FFFD}\nContent-Length: 3\n\nab\u{FFFD}"
+    );
+}
+
+#[test]
+fn test_comma_list() {
+    assert_eq!(http::parse::comma_list(b"a, b ,c"), vec![&b"a"[..], &b"b"[..], &b"c"[..]]);
+    assert_eq!(http::parse::comma_list(b", a,\tb ,, "), vec![&b"a"[..], &b"b"[..]]);
+    assert_eq!(http::parse::comma_list(b"gzip"), vec![&b"gzip"[..]]);
+    assert!(http::parse::comma_list(b" , ").is_empty());
+}
+
+#[test]
+fn test_connection_tokens() {
+    assert_eq!(http::parse::connection_tokens(b"keep-alive, Upgrade"), vec![&b"keep-alive"[..], &b"Upgrade"[..]]);
+    assert_eq!(http::parse::connection_tokens(b" close ,, "), vec![&b"close"[..]]);
+    assert!(http::parse::connection_tokens(b"").is_empty());
+}
+
+#[test]
+fn test_request_connection_tokens() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: keep-alive, Upgrade\r\n\
+        Connection: TE\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.connection_tokens(), vec![&b"keep-alive"[..], &b"Upgrade"[..], &b"TE"[..]]);
+}
+
+#[test]
+fn test_accept_encoding() {
+    assert_eq!(
+        http::parse::accept_encoding(b"gzip, deflate, br"),
+        vec![(&b"gzip"[..], 1.0), (&b"deflate"[..], 1.0), (&b"br"[..], 1.0)]
+    );
+    assert_eq!(
+        http::parse::accept_encoding(b"identity;q=0.5, gzip;q=1.0"),
+        vec![(&b"gzip"[..], 1.0), (&b"identity"[..], 0.5)]
+    );
+    assert_eq!(
+        http::parse::accept_encoding(b"identity;q=0, *;q=0.1, br; Q=0.9"),
+        vec![(&b"br"[..], 0.9), (&b"*"[..], 0.1), (&b"identity"[..], 0.0)]
+    );
+    assert_eq!(http::parse::accept_encoding(b"gzip;q=2, br;q=x, deflate"), vec![(&b"deflate"[..], 1.0)]);
+    assert!(http::parse::accept_encoding(b"").is_empty());
+}
+
+#[test]
+fn test_accept_language() {
+    assert_eq!(
+        http::parse::accept_language(b"en;q=0.7,en-US;q=0.8,en-ZA,en-GB;q=0.9"),
+        vec![(&b"en-ZA"[..], 1.0), (&b"en-GB"[..], 0.9), (&b"en-US"[..], 0.8), (&b"en"[..], 0.7)]
+    );
+    assert_eq!(http::parse::accept_language(b"*"), vec![(&b"*"[..], 1.0)]);
+}
+
+#[test]
+fn test_request_body_length() {
+    let cases = [
+        ("Content-Length: 5\r\n", Ok(BodyLength::Fixed(5))),
+        ("Content-Length: 0\r\n", Ok(BodyLength::Fixed(0))),
+        ("Transfer-Encoding: gzip, chunked\r\n", Ok(BodyLength::Chunked)),
+        ("", Ok(BodyLength::None)),
+        ("Transfer-Encoding: gzip\r\n", Err(ParserError::TransferEncoding)),
+        ("Content-Length: abc\r\n", Err(ParserError::ContentLength)),
+        ("Content-Length: 5\r\nTransfer-Encoding: chunked\r\n", Err(ParserError::ConflictingFraming)),
+    ];
+
+    for (framing, body_length) in cases.iter() {
+
+        let data = format!("POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n{}", framing);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        let _ = request.parse(data.as_bytes());
+
+        assert_eq!(request.body_length(), *body_length, "{}", framing);
+    }
+}
+
+#[test]
+fn test_request_chunked_body() {
+    let data = "\
+        POST /upload HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        4\r\n\
+        Wiki\r\n\
+        5\r\n\
+        pedia\r\n\
+        0\r\n\
+        \r\n\
+        GET / HTTP/1.1\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_remaining(data.as_bytes()), Ok(&b"GET / HTTP/1.1\r\n"[..]));
+    assert_eq!(request.body(), b"4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n");
+}
+
+#[test]
+fn test_chunked_decoder() {
+    let data = b"4\r\nWiki\r\n5\r\npedia\r\nE\r\n in\r\n\r\nchunks.\r\n0\r\n\r\nGET";
+
+    let mut decoder = ChunkedDecoder::new(data);
+
+    let chunks = decoder.by_ref().collect::<Result<Vec<_>, _>>();
+
+    assert_eq!(chunks, Ok(vec![&b"Wiki"[..], &b"pedia"[..], &b" in\r\n\r\nchunks."[..]]));
+    assert_eq!(chunks.unwrap().concat(), b"Wikipedia in\r\n\r\nchunks.".to_vec());
+    assert_eq!(decoder.remaining(), b"GET");
+    assert_eq!(decoder.next(), None);
+}
+
+#[test]
+fn test_chunked_decoder_malformed() {
+    let mut decoder = ChunkedDecoder::new(b"4\r\nWiki\r\nzz\r\n");
+
+    assert_eq!(decoder.next(), Some(Ok(&b"Wiki"[..])));
+    assert_eq!(decoder.next(), Some(Err(ParserError::Body)));
+    assert_eq!(decoder.next(), None);
+}
+
+#[test]
+fn test_request_target() {
+    assert_eq!(http::parse::request_target(b"OPTIONS", b"* HTTP/1.1\r\n"), Ok((&b" HTTP/1.1\r\n"[..], RequestTarget::Asterisk)));
+    assert_eq!(
+        http::parse::request_target(b"GET", b"/where"),
+        Ok((&b""[..], RequestTarget::Origin { path: b"/where", query: None }))
+    );
+    assert_eq!(
+        http::parse::request_target(b"GET", b"https://example.org:8443?a=1"),
+        Ok((&b""[..], RequestTarget::Absolute { scheme: b"https", authority: b"example.org:8443", path: b"", query: Some(b"a=1") }))
+    );
+    assert_eq!(
+        http::parse::request_target(b"GET", b"urn:isbn:0451450523"),
+        Ok((&b""[..], RequestTarget::Absolute { scheme: b"urn", authority: b"", path: b"isbn:0451450523", query: None }))
+    );
+    assert_eq!(
+        http::parse::request_target(b"CONNECT", b"[::1]:443"),
+        Ok((&b""[..], RequestTarget::Authority { host: b"[::1]", port: Some(443) }))
+    );
+    assert_eq!(
+        http::parse::request_target(b"CONNECT", b"example.org:http"),
+        Err(Err::Error((&b"example.org:http"[..], ErrorKind::Verify)))
+    );
+    assert_eq!(
+        http::parse::request_target(b"GET", b"1http://example.org/"),
+        Err(Err::Error((&b"1http://example.org/"[..], ErrorKind::Verify)))
+    );
+    assert_eq!(
+        http::parse::request_target(b"GET", b"example"),
+        Err(Err::Error((&b"example"[..], ErrorKind::Verify)))
+    );
+}
+
+#[test]
+fn test_header_with_tab_whitespace() {
+    let mut test_header = http::header::EMPTY_HEADER;
+
+    let (_, _) = http::parse::header(b"Host:\texample.com\r\n", &mut test_header).unwrap();
+
+    assert_eq!((test_header.name(), test_header.value()), (&b"Host"[..], &b"example.com"[..]));
+
+    let (_, _) = http::parse::header(b"Host: \t  example.com\r\n", &mut test_header).unwrap();
+
+    assert_eq!(test_header.value(), &b"example.com"[..]);
+}
+
+#[test]
+fn test_content_range() {
+    assert_eq!(
+        http::parse::content_range(b"bytes 0-499/1234"),
+        Ok((&b""[..], ContentRange { range: Some((0, 499)), total: Some(1234) }))
+    );
+    assert_eq!(
+        http::parse::content_range(b"bytes 0-499/*"),
+        Ok((&b""[..], ContentRange { range: Some((0, 499)), total: None }))
+    );
+    assert_eq!(
+        http::parse::content_range(b"bytes */1234"),
+        Ok((&b""[..], ContentRange { range: None, total: Some(1234) }))
+    );
+    assert!(http::parse::content_range(b"bytes */*").is_err());
+    assert!(http::parse::content_range(b"bytes 500-499/1234").is_err());
+    assert!(http::parse::content_range(b"bytes 0-1234/1234").is_err());
+    assert!(http::parse::content_range(b"items 0-4/10").is_err());
+}
+
+#[test]
+fn test_response_content_range() {
+    let mut response = Response::new();
+
+    assert_eq!(response.content_range(), None);
+
+    response.add_header(b"content-range", b"bytes */1234");
+
+    assert_eq!(response.content_range(), Some(ContentRange { range: None, total: Some(1234) }));
+}
+
+#[test]
+fn test_request_body_too_large() {
+    let mut config = ParserConfig::new();
+    config.max_body_bytes = 8;
+
+    // The declared length is rejected without the body being present
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 1048576\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_with_config(data.as_bytes(), &config), Err(ParserError::BodyTooLarge));
+
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+        5\r\n\
+        world\r\n\
+        0\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_with_config(data.as_bytes(), &config), Err(ParserError::BodyTooLarge));
+
+    config.max_body_bytes = 10;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_with_config(data.as_bytes(), &config), Ok(()));
+}
+
+#[test]
+fn test_method_round_trip() {
+    let methods = [
+        ("GET", Method::Get),
+        ("HEAD", Method::Head),
+        ("POST", Method::Post),
+        ("PUT", Method::Put),
+        ("DELETE", Method::Delete),
+        ("CONNECT", Method::Connect),
+        ("OPTIONS", Method::Options),
+        ("TRACE", Method::Trace),
+        ("PATCH", Method::Patch),
+        ("PROPFIND", Method::Extension("PROPFIND".to_string())),
+        ("get", Method::Extension("get".to_string())),
+    ];
+
+    for (name, method) in methods.iter() {
+        assert_eq!(name.parse::<Method>(), Ok(method.clone()));
+        assert_eq!(method.to_string(), *name);
+    }
+}
+
+#[test]
+fn test_method_semantics() {
+    let methods = [
+        (Method::Get, true, true),
+        (Method::Head, true, true),
+        (Method::Post, false, false),
+        (Method::Put, false, true),
+        (Method::Delete, false, true),
+        (Method::Connect, false, false),
+        (Method::Options, true, true),
+        (Method::Trace, true, true),
+        (Method::Patch, false, false),
+        (Method::Extension("PROPFIND".to_string()), false, false),
+    ];
+
+    for (method, safe, idempotent) in methods.iter() {
+        assert_eq!(method.is_safe(), *safe, "{}", method);
+        assert_eq!(method.is_idempotent(), *idempotent, "{}", method);
+    }
+}
+
+#[test]
+fn test_request_without_body_consumes_empty_line() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_remaining(data.as_bytes()), Ok(&b""[..]));
+}
+
+#[test]
+fn test_request_too_many_headers() {
+    let mut headers = [http::header::EMPTY_HEADER; 2];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(
+        request.parse(b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nAccept: text/html\r\nPragma: no-cache\r\n\r\n"),
+        Err(ParserError::TooManyHeaders)
+    );
+
+    let mut headers = [http::header::EMPTY_HEADER; 2];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nAccept: text/html\r\n\r\n"), Ok(()));
+}
+
+#[test]
+fn test_request_version_tuple() {
+    let versions = [
+        ("1.1", Ok((1, 1))),
+        ("1.0", Ok((1, 0))),
+        ("2", Ok((2, 0))),
+        ("3", Ok((3, 0))),
+    ];
+
+    for (version, tuple) in versions.iter() {
+
+        let data = format!("GET / HTTP/{}\r\nHost: 127.0.0.1:9000\r\n", version);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        match request.parse(data.as_bytes()) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(request.version_tuple(), *tuple, "HTTP/{}", version);
+    }
+
+    // The same error as Version::from_bytes
+    for version in ["x", "1.", "4", "1.1.1"].iter() {
+
+        let request = Request::build().version(version).build();
+
+        assert_eq!(request.version_tuple(), Err(ParserError::InvalidVersion), "{:?}", version);
+        assert_eq!(Version::from_bytes(version.as_bytes()), Err(ParserError::InvalidVersion));
+    }
+}
+
+#[test]
+fn test_request_headers_end_at_buffer_boundary() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Accept: text/html\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_remaining(data.as_bytes()), Ok(&b""[..]));
+    assert_eq!(request.header_count(), 2);
+
+    // A body that is expected but missing is a body failure, not a header failure
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Content-Length: 5\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data.as_bytes()), Err(ParserError::IncompleteBody { expected: 5, got: 0 }));
+}
+
+#[test]
+fn test_request_parse_partial_headers() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Accept: text/html\r\n\
+        Pragma: no-cache\r\n\
+        \r\n\
+    ";
+
+    let split = data.find("text/").unwrap();
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_partial(&data.as_bytes()[..split]), Ok(Status::Partial));
+    assert_eq!(request.header_count(), 1);
+    assert_eq!(request.headers()[0].value(), b"127.0.0.1:9000");
+
+    assert_eq!(request.parse_partial(data.as_bytes()), Ok(Status::Complete(data.len())));
+    assert_eq!(request.header_count(), 3);
+    assert_eq!(request.header("Accept").map(|h| h.value()), Some(&b"text/html"[..]));
+}
+
+#[test]
+fn test_request_parse_partial() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+        0\r\n\
+        \r\n\
+        GET /next HTTP/1.1\r\n\
+    ";
+
+    let end = data.find("GET").unwrap();
+
+    // Every split before the end of the message reports it as partial
+    for split in 0..end {
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        assert_eq!(request.parse_partial(&data.as_bytes()[..split]), Ok(Status::Partial), "split at {}", split);
+    }
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_partial(data.as_bytes()), Ok(Status::Complete(end)));
+    assert_eq!(request.body(), b"5\r\nhello\r\n0\r\n\r\n");
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(
+        request.parse_partial(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nhello"),
+        Ok(Status::Partial)
+    );
+    assert_eq!(
+        request.parse_partial(b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n"),
+        Err(ParserError::Body)
+    );
+    assert_eq!(request.parse_partial(b"GET / HTTP/1.1\r\nBad Header\r\n\r\n"), Err(ParserError::Headers));
+}
+
+#[test]
+fn test_response_body_length() {
+    let cases: [(&[u8], BodyLength); 6] = [
+        (b"HTTP/1.1 204 No Content\r\nContent-Length: 5\r\n\r\n", BodyLength::None),
+        (b"HTTP/1.1 304 Not Modified\r\nTransfer-Encoding: chunked\r\n\r\n", BodyLength::None),
+        (b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nContent-Length: 5\r\n\r\n0\r\n\r\n", BodyLength::Chunked),
+        (b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello", BodyLength::Fixed(5)),
+        (b"HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\n\r\nhello", BodyLength::CloseDelimited),
+        (b"HTTP/1.0 200 OK\r\n\r\nhello", BodyLength::CloseDelimited),
+    ];
+
+    for (data, length) in cases.iter() {
+
+        let mut response = Response::new();
+
+        match response.parse(data) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(response.body_length(), Ok(*length), "{}", String::from_utf8_lossy(data));
+    }
+
+    let mut response = Response::new();
+
+    response.status = 200;
+    response.add_header(b"Content-Length", b"five");
+
+    assert_eq!(response.body_length(), Err(ParserError::ContentLength));
+}
+
+#[test]
+fn test_response_parse_partial() {
+    let data = "\
+        HTTP/1.1 200 OK\r\n\
+        Content-Type: text/plain\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+        0\r\n\
+        \r\n\
+        HTTP/1.1 204 No Content\r\n\
+    ";
+
+    let end = data.find("HTTP/1.1 204").unwrap();
+
+    // Every split before the end of the message reports it as partial
+    for split in 0..end {
+
+        let mut response = Response::new();
+
+        assert_eq!(response.parse_partial(&data.as_bytes()[..split]), Ok(Status::Partial), "split at {}", split);
+    }
+
+    let mut response = Response::new();
+
+    assert_eq!(response.parse_partial(data.as_bytes()), Ok(Status::Complete(end)));
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.body(), b"5\r\nhello\r\n0\r\n\r\n");
+
+    let data = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+
+    assert_eq!(response.parse_partial(&data[..data.len() - 2]), Ok(Status::Partial));
+    assert_eq!(response.parse_partial(data), Ok(Status::Complete(data.len())));
+    assert_eq!(response.body(), b"hello");
+
+    // A body delimited by the connection closing is never known to be complete
+    assert_eq!(response.parse_partial(b"HTTP/1.0 200 OK\r\n\r\nhello"), Ok(Status::Partial));
+    assert_eq!(response.parse_partial(b"HTTP/1.1 304 Not Modified\r\n\r\n"), Ok(Status::Complete(29)));
+    assert_eq!(
+        response.parse_partial(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n"),
+        Err(ParserError::Body)
+    );
+}
+
+#[test]
+fn test_response_parse_all() {
+    let data = "\
+        HTTP/1.1 200 OK\r\n\
+        Content-Length: 5\r\n\
+        \r\n\
+        hello\
+        HTTP/1.1 304 Not Modified\r\n\
+        ETag: \"abc\"\r\n\
+        \r\n\
+        HTTP/1.1 200 OK\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        world\r\n\
+        0\r\n\
+        \r\n\
+        HTTP/1.1 200 OK\r\n\
+        Content-Length: 5\r\n\
+        \r\n\
+        par\
+    ";
+
+    let (responses, offset) = match Response::parse_all(data.as_bytes()) {
+        Ok(parsed) => parsed,
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    };
+
+    // The incomplete trailing response starts at the returned offset
+    assert_eq!(&data[offset..], "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\npar");
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0].body(), b"hello");
+    assert_eq!(responses[1].status(), 304);
+    assert_eq!(responses[1].body(), b"");
+    assert_eq!(responses[2].body(), b"5\r\nworld\r\n0\r\n\r\n");
+
+    assert_eq!(Response::parse_all(b"").map(|(responses, offset)| (responses.len(), offset)), Ok((0, 0)));
+    assert_eq!(Response::parse_all(b"HTTP/1.1 204 No Content\r\n\r\n").map(|(_, offset)| offset), Ok(27));
+    assert!(Response::parse_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1\r\n\r\naGARBAGE\r\n").is_err());
+}
+
+#[test]
+fn test_response_to_bytes() {
+    let mut response = Response::new();
+
+    response.version = b"1.1";
+    response.status = 200;
+    response.reason = b"OK";
+    response.body = b"\x89PNG\r\n\x1a\n\x00\xff";
+
+    response.add_header(b"Content-Type", b"image/png");
+
+    assert_eq!(
+        response.to_bytes(),
+        b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\r\n\x89PNG\r\n\x1a\n\x00\xff".to_vec()
+    );
+
+    let mut response = Response::new();
+
+    response.version = b"1.1";
+    response.status = 204;
+    response.reason = b"No Content";
+
+    assert_eq!(response.to_bytes(), b"HTTP/1.1 204 No Content\r\n\r\n".to_vec());
+    assert_eq!(String::from(response), "HTTP/1.1 204 No Content\r\n\r\n");
+}
+
+#[test]
+fn test_header_is_empty() {
+    let mut test_header = http::header::EMPTY_HEADER;
+
+    assert!(test_header.is_empty());
+
+    let (_, _) = http::parse::header(b"X-Empty:\r\n", &mut test_header).unwrap();
+
+    assert!(!test_header.is_empty());
+}
+
+#[test]
+fn test_request_with_vec() {
+    let mut data = "GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n".to_string();
+
+    for i in 0..49 {
+        data.push_str(&format!("X-Header-{}: {}\r\n", i, i));
+    }
+
+    data.push_str("\r\n");
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data.as_bytes()), Err(ParserError::TooManyHeaders));
+
+    let mut request = Request::with_vec(Vec::new());
+
+    assert_eq!(request.parse_remaining(data.as_bytes()), Ok(&b""[..]));
+    assert_eq!(request.header_count(), 50);
+    assert_eq!(request.headers().len(), 50);
+    assert_eq!(request.header("X-Header-48").map(|h| h.value()), Some(&b"48"[..]));
+
+    assert_eq!(request.parse(b"GET / HTTP/1.1\r\n\r\n"), Ok(()));
+    assert_eq!(request.headers().len(), 0);
+}
+
+#[test]
+fn test_request_parse_headers_only() {
+    let data = b"POST /upload HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    let offset = match request.parse_headers_only(data) {
+        Ok(offset) => offset,
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    };
+
+    assert_eq!(&data[offset..], &b"5\r\nhello\r\n"[..]);
+    assert_eq!(&data[..offset], &b"POST /upload HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\n"[..]);
+    assert_eq!(request.header_count(), 2);
+    assert!(request.body().is_empty());
+
+    // The body is not framed, so its length is not checked
+    assert_eq!(request.parse_headers_only(b"POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n"), Ok(38));
+}
+
+#[test]
+fn test_www_authenticate() {
+    let (_, challenges) = http::parse::www_authenticate(b"Digest realm=\"test\", nonce=\"abc\"").unwrap();
+
+    assert_eq!(challenges, vec![Challenge { scheme: b"Digest", token68: None, params: vec![(&b"realm"[..], Cow::from(&b"test"[..])), (&b"nonce"[..], Cow::from(&b"abc"[..]))] }]);
+
+    let (_, challenges) = http::parse::www_authenticate(b"Basic realm=\"a, b\", Bearer, Digest realm=x, qop=\"auth\"").unwrap();
+
+    assert_eq!(challenges.len(), 3);
+    assert_eq!(challenges[0].param("realm"), Some(&b"a, b"[..]));
+    assert_eq!(challenges[1].scheme, b"Bearer");
+    assert!(challenges[1].params.is_empty());
+    assert_eq!(challenges[2].param("QOP"), Some(&b"auth"[..]));
+
+    let (_, challenges) = http::parse::www_authenticate(b"Digest realm=\"say \\\"hi\\\"\"").unwrap();
+
+    assert_eq!(challenges[0].param("realm"), Some(&b"say \"hi\""[..]));
+
+    assert!(http::parse::www_authenticate(b"").is_err());
+
+    let mut response = Response::new();
+
+    response.add_header(b"WWW-Authenticate", b"Digest realm=\"test\", nonce=\"abc\"");
+
+    assert_eq!(response.www_authenticate()[0].param("nonce"), Some(&b"abc"[..]));
+}
+
+#[test]
+fn test_www_authenticate_token68() {
+    let (_, challenges) = http::parse::www_authenticate(b"Negotiate abc==, Basic realm=\"x\"").unwrap();
+
+    assert_eq!(challenges.len(), 2);
+    assert_eq!(challenges[0].scheme, b"Negotiate");
+    assert_eq!(challenges[0].token68, Some(&b"abc=="[..]));
+    assert!(challenges[0].params.is_empty());
+    assert_eq!(challenges[1].scheme, b"Basic");
+    assert_eq!(challenges[1].token68, None);
+    assert_eq!(challenges[1].param("realm"), Some(&b"x"[..]));
+
+    // A token68 without padding that ends the header
+    let (_, challenges) = http::parse::www_authenticate(b"Basic realm=x, NTLM TlRMTVNTUAAB").unwrap();
+
+    assert_eq!(challenges.len(), 2);
+    assert_eq!(challenges[1].token68, Some(&b"TlRMTVNTUAAB"[..]));
+
+    assert_eq!(http::parse::token68(b"a+/b==, x"), Ok((&b", x"[..], &b"a+/b=="[..])));
+    assert!(http::parse::token68(b"=abc").is_err());
+}
+
+#[test]
+fn test_request_invalid_version() {
+    let versions = [
+        ("1.1", Ok(())),
+        ("1.0", Ok(())),
+        ("2", Ok(())),
+        ("1.2.3", Err(ParserError::InvalidVersion)),
+        ("1..1", Err(ParserError::InvalidVersion)),
+        ("99.99", Err(ParserError::InvalidVersion)),
+        ("4", Err(ParserError::InvalidVersion)),
+        ("1", Err(ParserError::InvalidVersion)),
+    ];
+
+    for (version, result) in versions.iter() {
+
+        let data = format!("GET / HTTP/{}\r\nHost: 127.0.0.1:9000\r\n\r\n", version);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        assert_eq!(request.parse(data.as_bytes()), *result, "HTTP/{}", version);
+    }
+}
+
+#[test]
+fn test_version_from_bytes() {
+    assert_eq!(Version::from_bytes(b"1.1"), Ok(Version::HTTP_11));
+    assert_eq!(Version::from_bytes(b"2"), Ok(Version::HTTP_20));
+    assert_eq!(Version::from_bytes(b"0.9"), Ok(Version { major: 0, minor: 9 }));
+    assert_eq!(Version::from_bytes(b"12.0"), Err(ParserError::InvalidVersion));
+    assert_eq!(Version::from_bytes(b"1.10"), Err(ParserError::InvalidVersion));
+    assert_eq!(Version::from_bytes(b""), Err(ParserError::InvalidVersion));
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n"), Ok(()));
+    assert_eq!(request.parse(b"GET / HTTP/12.0\r\nHost: 127.0.0.1:9000\r\n\r\n"), Err(ParserError::InvalidVersion));
+}
+
+#[test]
+fn test_request_take_body() {
+    let data = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nContent-Length: 5\r\n\r\nhello";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.take_body(), b"hello");
+    assert_eq!(request.take_body(), b"");
+    assert_eq!(request.body(), b"");
+}
+
+#[test]
+fn test_request_transfer_decoded_body() {
+    let data = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: identity, chunked\r\n\r\n3\r\nhel\r\n2\r\nlo\r\n0\r\n\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.transfer_decoded_body(), Ok(b"hello".to_vec()));
+
+    let data = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: br, chunked\r\n\r\n3\r\nhel\r\n0\r\n\r\n";
+
+    assert_eq!(request.parse(data), Ok(()));
+    assert_eq!(request.transfer_decoded_body(), Err(ParserError::UnsupportedTransferEncoding));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn test_request_transfer_decoded_body_gzip_chunked() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"{\"test\": \"data\"}").unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let (first, second) = compressed.split_at(compressed.len() / 2);
+
+    let mut data = b"\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: gzip, chunked\r\n\
+        \r\n\
+    ".to_vec();
+
+    for chunk in [first, second].iter() {
+        data.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+        data.extend_from_slice(chunk);
+        data.extend_from_slice(b"\r\n");
+    }
+
+    data.extend_from_slice(b"0\r\n\r\n");
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(&data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.transfer_decoded_body(), Ok(b"{\"test\": \"data\"}".to_vec()));
+}
+
+#[test]
+fn test_request_header_combined() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Accept: text/html\r\n\
+        Set-Cookie: a=1\r\n\
+        accept: application/json\r\n\
+        Set-Cookie: b=2\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.header_combined("Accept"), Some(b"text/html, application/json".to_vec()));
+    assert_eq!(request.header_combined("Host"), Some(b"127.0.0.1:9000".to_vec()));
+    assert_eq!(request.header_combined("Via"), None);
+    assert_eq!(request.header_combined("Set-Cookie"), None);
+}
+
+#[test]
+fn test_ows() {
+    assert_eq!(http::parse::ows(b""), Ok((&b""[..], &b""[..])));
+    assert_eq!(http::parse::ows(b"   "), Ok((&b""[..], &b"   "[..])));
+    assert_eq!(http::parse::ows(b" \t \tvalue"), Ok((&b"value"[..], &b" \t \t"[..])));
+    assert_eq!(http::parse::ows(b"value "), Ok((&b"value "[..], &b""[..])));
+
+    assert_eq!(http::parse::bws(b"\t= x"), Ok((&b"= x"[..], &b"\t"[..])));
+
+    assert_eq!(http::parse::rws(b" \tvalue"), Ok((&b"value"[..], &b" \t"[..])));
+    assert_eq!(http::parse::rws(b""), Err(Err::Error((&b""[..], ErrorKind::TakeWhile1))));
+    assert_eq!(http::parse::rws(b"value"), Err(Err::Error((&b"value"[..], ErrorKind::TakeWhile1))));
+}
+
+#[test]
+fn test_request_content_type() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Pragma: no-cache\r\n\
+        Cache-Control: no-cache\r\n\
+        User-Agent: Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/83.0.4103.61 Safari/537.36\r\n\
+        Origin: http://local.test.tld\r\n\
+        Content-Length: 16\r\n\
+        Content-Type: application/json\r\n\
+        \r\n\
+        {\"test\": \"data\"}\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.content_type(), Some(ContentType { media_type: b"application", subtype: b"json", params: vec![] }));
+
+    let (_, content_type) = http::parse::content_type(b"text/html; charset=\"utf-8\";boundary=x").unwrap();
+
+    assert_eq!(content_type.media_type, b"text");
+    assert_eq!(content_type.subtype, b"html");
+    assert_eq!(content_type.param("Charset"), Some(&b"utf-8"[..]));
+    assert_eq!(content_type.param("boundary"), Some(&b"x"[..]));
+
+    let (_, content_type) = http::parse::content_type(b"text/plain; title=\"a \\\"b\\\" c\"").unwrap();
+
+    assert_eq!(content_type.param("title"), Some(&b"a \"b\" c"[..]));
+
+    assert!(http::parse::content_type(b"text").is_err());
+}
+
+#[test]
+fn test_quoted_string() {
+    assert_eq!(http::parse::quoted_string(b"\"a b.txt\"; x"), Ok((&b"; x"[..], b"a b.txt".to_vec())));
+    assert_eq!(http::parse::quoted_string(b"\"say \\\"hi\\\"\""), Ok((&b""[..], b"say \"hi\"".to_vec())));
+    assert_eq!(http::parse::quoted_string(b"\"a\\\\b\""), Ok((&b""[..], b"a\\b".to_vec())));
+    assert_eq!(http::parse::quoted_string(b"\"\""), Ok((&b""[..], Vec::new())));
+
+    assert!(http::parse::quoted_string(b"utf-8").is_err());
+    assert!(http::parse::quoted_string(b"\"unterminated").is_err());
+    assert!(http::parse::quoted_string(b"\"bad\x01\"").is_err());
+}
+
+#[test]
+fn test_content_disposition() {
+    let (_, disposition) = http::parse::content_disposition(b"attachment; filename=\"f.txt\"").unwrap();
+
+    assert_eq!(disposition, ContentDisposition { disposition_type: b"attachment", params: vec![(&b"filename"[..], Cow::from(&b"f.txt"[..]))] });
+    assert_eq!(disposition.filename(), Some("f.txt".to_string()));
+
+    let (_, disposition) = http::parse::content_disposition(b"form-data; name=\"field\"").unwrap();
+
+    assert_eq!(disposition.disposition_type, b"form-data");
+    assert_eq!(disposition.name(), Some(&b"field"[..]));
+    assert_eq!(disposition.filename(), None);
+
+    // The extended parameter is preferred over the plain one
+    let (_, disposition) = http::parse::content_disposition(b"attachment;filename=\"EURO rates\";filename*=utf-8''%e2%82%ac%20rates").unwrap();
+
+    assert_eq!(disposition.param("FILENAME"), Some(&b"EURO rates"[..]));
+    assert_eq!(disposition.filename(), Some("\u{20AC} rates".to_string()));
+
+    // Quoted-pairs in the plain parameter are unescaped
+    let (_, disposition) = http::parse::content_disposition(b"attachment; filename=\"a\\\"b.txt\"").unwrap();
+
+    assert_eq!(disposition.filename(), Some("a\"b.txt".to_string()));
+
+    assert!(http::parse::content_disposition(b"; filename=f.txt").is_err());
+}
+
+#[test]
+fn test_response_date() {
+    let mut response = Response::new();
+
+    assert_eq!(response.date(), None);
+
+    response.add_header(b"Date", b"Sun, 06 Nov 1994 08:49:37 GMT");
+
+    assert_eq!(response.date(), Some(HttpDate { year: 1994, month: 11, day: 6, hour: 8, minute: 49, second: 37 }));
+
+    response.set_header(b"date", b"yesterday");
+
+    assert_eq!(response.date(), None);
+}
+
+#[test]
+fn test_status_line() {
+    assert_eq!(http::parse::status_line(b"HTTP/1.1 200 OK\r\n"), Ok((&b""[..], (&b"1.1"[..], 200, &b"OK"[..]))));
+    assert_eq!(http::parse::status_line(b"HTTP/1.1 204\r\n"), Ok((&b""[..], (&b"1.1"[..], 204, &b""[..]))));
+    assert_eq!(http::parse::status_line(b"HTTP/1.1 204 \r\n"), Ok((&b""[..], (&b"1.1"[..], 204, &b""[..]))));
+    assert_eq!(
+        http::parse::status_line(b"HTTP/1.0 404 Not Found\r\nServer: test\r\n"),
+        Ok((&b"Server: test\r\n"[..], (&b"1.0"[..], 404, &b"Not Found"[..])))
+    );
+
+    assert!(http::parse::status_line(b"HTTP/1.1 20\r\n").is_err());
+    assert!(http::parse::status_line(b"HTTP/1.1 2000 OK\r\n").is_err());
+    assert!(http::parse::status_line(b"HTTP/1.1 +20 OK\r\n").is_err());
+}
+
+#[test]
+fn test_parser_feed_one_byte_at_a_time() {
+    let data = b"POST /upload HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nContent-Length: 5\r\n\r\nhello";
+
+    let mut parser = Parser::new();
+
+    for (i, byte) in data.iter().enumerate() {
+
+        let status = match parser.feed(&[*byte]) {
+            Ok(status) => status,
+            Err(e) => panic!("Something went wrong at byte {}: {:?}", i, e)
+        };
+
+        match i == data.len() - 1 {
+            true => assert_eq!(status, Status::Complete(data.len())),
+            false => {
+                assert_eq!(status, Status::Partial, "byte {}", i);
+                assert!(parser.request().is_none());
+            }
+        }
+    }
+
+    let request = parser.request().unwrap();
+
+    assert_eq!(request.method(), b"POST");
+    assert_eq!(request.path(), b"/upload");
+    assert_eq!(request.header_count(), 2);
+    assert_eq!(request.body(), b"hello");
+}
+
+#[test]
+fn test_parser_feed_chunked() {
+    let data = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nhel\r\n2\r\nlo\r\n0\r\n\r\n";
+
+    let mut parser = Parser::new();
+
+    for byte in data[..data.len() - 1].iter() {
+        assert_eq!(parser.feed(&[*byte]), Ok(Status::Partial));
+    }
+
+    assert_eq!(parser.feed(&data[data.len() - 1..]), Ok(Status::Complete(data.len())));
+    assert_eq!(parser.feed(b"GET"), Ok(Status::Complete(data.len())));
+    assert_eq!(parser.request().unwrap().transfer_decoded_body(), Ok(b"hello".to_vec()));
+
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.feed(b"GET / HTTP/1.1\r\nHost 127.0.0.1\r\n"), Ok(Status::Partial));
+    assert_eq!(parser.feed(b"\r\n"), Err(ParserError::Headers));
+
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.feed(b"GET / HTTP/1.1\r\n\r\n5\r\nhel"), Ok(Status::Complete(18)));
+    assert_eq!(parser.feed(b"GET/ HTTP/1.1\r\n\r\n"), Ok(Status::Complete(18)));
+
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.feed(b"GET/ HTTP/1.1\r\n"), Err(ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::Tag }));
+}
+
+#[test]
+fn test_request_syntax_error_kind() {
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(
+        request.parse(b"GET / HTTQ/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n"),
+        Err(ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::Tag })
+    );
+    assert_eq!(
+        request.parse(b"GET / HTTP/\r\nHost: 127.0.0.1:9000\r\n\r\n"),
+        Err(ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::TakeWhile1 })
+    );
+    assert_eq!(
+        request.parse_partial(b"GET / HTTP/1.1 \r\n\r\n"),
+        Err(ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::Tag })
+    );
+
+    let error = ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::Tag };
+
+    assert_eq!(error.to_string(), "ParserError: Invalid HTTP Message request line syntax (Tag).");
+}
+
+#[test]
+fn test_request_eq() {
+    let data = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nContent-Length: 5\r\n\r\nhello";
+
+    let mut first_headers = [http::header::EMPTY_HEADER; 32];
+    let mut second_headers = [http::header::EMPTY_HEADER; 4];
+
+    let mut first = Request::new(&mut first_headers);
+    let mut second = Request::new(&mut second_headers);
+
+    assert_eq!(first.parse(data), Ok(()));
+    assert_eq!(second.parse(data), Ok(()));
+    assert_eq!(first, second);
+
+    let mut owned = Request::with_vec(Vec::new());
+
+    assert_eq!(owned.parse(data), Ok(()));
+    assert_eq!(first, owned);
+
+    assert_eq!(second.parse(b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nContent-Length: 5\r\n\r\nworld"), Ok(()));
+    assert_ne!(first, second);
+
+    assert_eq!(second.parse(b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\nhello"), Ok(()));
+    assert_ne!(first, second);
+}
+
+#[test]
+fn test_request_build() {
+    let request = Request::build()
+        .method("POST")
+        .path("/x")
+        .version("1.1")
+        .header("Host", "h")
+        .header("Accept", "*/*")
+        .body(b"data")
+        .build();
+
+    assert_eq!(request.method(), b"POST");
+    assert_eq!(request.header_count(), 2);
+    assert_eq!(request.to_bytes(), b"POST /x HTTP/1.1\r\nHost: h\r\nAccept: */*\r\nContent-Length: 4\r\n\r\ndata".to_vec());
+
+    let bytes = request.to_bytes();
+
+    let mut parsed = Request::with_vec(Vec::new());
+
+    match parsed.parse(&bytes) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(parsed.body(), b"data");
+    assert_eq!(parsed.content_length(), Some(Ok(4)));
+
+    assert_eq!(Request::build().build().to_bytes(), b"GET / HTTP/1.1\r\n\r\n".to_vec());
+    assert_eq!(
+        Request::build().header("content-length", "4").body(b"data").build().to_bytes(),
+        b"GET / HTTP/1.1\r\ncontent-length: 4\r\n\r\ndata".to_vec()
+    );
+}
+
+#[test]
+fn test_request_path_decoded() {
+    let paths = [
+        ("/check/%E2%9C%93?done=%E2%9C%93", Ok("/check/\u{2713}".to_string())),
+        ("/caf\u{e9}/%7Euser", Ok("/caf\u{e9}/~user".to_string())),
+        ("/plain", Ok("/plain".to_string())),
+        ("/bad/%E2%9C", Err(())),
+        ("/bad/%G1", Err(())),
+    ];
+
+    for (path, decoded) in paths.iter() {
+
+        let data = format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n", path);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        match request.parse(data.as_bytes()) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(request.path_decoded().map_err(|_| ()), *decoded, "{}", path);
+    }
+}
+
+#[test]
+fn test_request_header_bytes() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: Upgrade\r\n\
+        Pragma: no-cache\r\n\
+        Cache-Control: no-cache\r\n\
+        User-Agent: Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/83.0.4103.61 Safari/537.36\r\n\
+        Upgrade: websocket\r\n\
+        Origin: http://local.test.tld\r\n\
+        Sec-WebSocket-Version: 13\r\n\
+        Accept-Encoding: gzip, deflate, br\r\n\
+        Accept-Language: en-ZA,en-GB;q=0.9,en-US;q=0.8,en;q=0.7\r\n\
+        Sec-WebSocket-Key: t/p5xBb6yGX25WLXAjeS0A==\r\n\
+        Sec-WebSocket-Extensions: permessage-deflate; client_max_window_bits\r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(
+        request.header_bytes("user-agent"),
+        Some(&b"Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/83.0.4103.61 Safari/537.36"[..])
+    );
+    assert_eq!(request.header_bytes("Content-Type"), None);
+}
+
+#[test]
+fn test_cache_control() {
+    assert_eq!(http::parse::cache_control(b"no-cache"), vec![CacheDirective::NoCache]);
+    assert_eq!(
+        http::parse::cache_control(b"max-age=3600, must-revalidate"),
+        vec![CacheDirective::MaxAge(3600), CacheDirective::MustRevalidate]
+    );
+    assert_eq!(
+        http::parse::cache_control(b"Private=\"Set-Cookie\", max-stale, s-maxage=\"60\", max-age=soon, ext=\"a\""),
+        vec![
+            CacheDirective::Private,
+            CacheDirective::MaxStale(None),
+            CacheDirective::SMaxAge(60),
+            CacheDirective::Extension(b"max-age", Some(b"soon")),
+            CacheDirective::Extension(b"ext", Some(b"a")),
+        ]
+    );
+    assert_eq!(http::parse::cache_control(b""), vec![]);
+
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Pragma: no-cache\r\n\
+        Cache-Control: no-cache\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.cache_control(), vec![CacheDirective::NoCache]);
+
+    let mut response = Response::new();
+
+    response.add_header(b"Cache-Control", b"max-age=3600, must-revalidate");
+    response.add_header(b"cache-control", b"public");
+
+    assert_eq!(
+        response.cache_control(),
+        vec![CacheDirective::MaxAge(3600), CacheDirective::MustRevalidate, CacheDirective::Public]
+    );
+}
+
+#[test]
+fn test_etag() {
+    let strong = ETag { weak: false, tag: b"abc" };
+    let weak = ETag { weak: true, tag: b"abc" };
+
+    assert_eq!(http::parse::etag(b"\"abc\""), Ok((&b""[..], strong)));
+    assert_eq!(http::parse::etag(b"W/\"abc\""), Ok((&b""[..], weak)));
+    assert_eq!(http::parse::etag(b"\"\""), Ok((&b""[..], ETag { weak: false, tag: b"" })));
+    assert!(http::parse::etag(b"abc").is_err());
+    assert!(http::parse::etag(b"w/\"abc\"").is_err());
+    assert!(http::parse::etag(b"\"a c\"").is_err());
+
+    assert!(strong.strong_eq(&strong));
+    assert!(!strong.strong_eq(&weak));
+    assert!(strong.weak_eq(&weak));
+
+    let mut response = Response::new();
+
+    response.add_header(b"ETag", b"W/\"abc\"");
+
+    assert_eq!(response.etag(), Some(weak));
+}
+
+#[test]
+fn test_request_if_none_match() {
+    assert_eq!(http::parse::if_none_match(b"*"), Ok((&b""[..], IfNoneMatch::Any)));
+    assert_eq!(
+        http::parse::if_none_match(b"\"xyzzy\", W/\"r2d2xxxx\" ,\"c3piozzzz\""),
+        Ok((&b""[..], IfNoneMatch::Tags(vec![
+            ETag { weak: false, tag: b"xyzzy" },
+            ETag { weak: true, tag: b"r2d2xxxx" },
+            ETag { weak: false, tag: b"c3piozzzz" },
+        ])))
+    );
+
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        If-None-Match: W/\"abc\", \"def\"\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    let condition = request.if_none_match().unwrap();
+
+    assert!(condition.matches(&ETag { weak: false, tag: b"abc" }));
+    assert!(condition.matches(&ETag { weak: true, tag: b"def" }));
+    assert!(!condition.matches(&ETag { weak: false, tag: b"xyz" }));
+    assert!(IfNoneMatch::Any.matches(&ETag { weak: false, tag: b"xyz" }));
+
+    assert_eq!(request.parse(b"GET / HTTP/1.1\r\nIf-None-Match: abc\r\n\r\n"), Ok(()));
+    assert_eq!(request.if_none_match(), None);
+}
+
+#[test]
+fn test_request_max_method_bytes() {
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(b"PROPFIND /file HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n"), Ok(()));
+    assert_eq!(request.method(), b"PROPFIND");
+
+    let data = format!("{} / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n", "A".repeat(100));
+
+    assert_eq!(request.parse(data.as_bytes()), Err(ParserError::MethodTooLong));
+    assert_eq!(request.parse_partial(data.as_bytes()), Err(ParserError::MethodTooLong));
+
+    let mut config = ParserConfig::new();
+    config.max_method_bytes = 100;
+
+    assert_eq!(request.parse_with_config(data.as_bytes(), &config), Ok(()));
+}
+
+#[test]
+fn test_crlf_or_lf() {
+    assert_eq!(http::parse::crlf_or_lf(b"\r\nHost"), Ok((&b"Host"[..], &b"\r\n"[..])));
+    assert_eq!(http::parse::crlf_or_lf(b"\nHost"), Ok((&b"Host"[..], &b"\n"[..])));
+    assert_eq!(http::parse::crlf_or_lf(b"\rHost"), Err(Err::Error((&b"\rHost"[..], ErrorKind::Tag))));
+    assert_eq!(http::parse::crlf_or_lf(b""), Err(Err::Error((&b""[..], ErrorKind::Tag))));
+}
+
+#[test]
+fn test_parser_consumed_and_state() {
+    let segments: [&[u8]; 3] = [
+        b"POST /upload HT",
+        b"TP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nh",
+        b"el\r\n0\r\n\r\n",
+    ];
+
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.state(), ParseState::RequestLine);
+    assert_eq!(parser.consumed(), 0);
+
+    let states = [ParseState::RequestLine, ParseState::Body, ParseState::Complete];
+
+    let mut consumed = 0;
+
+    for (segment, state) in segments.iter().zip(states.iter()) {
+
+        match parser.feed(segment) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(parser.state(), *state);
+        assert!(parser.consumed() >= consumed);
+
+        consumed = parser.consumed();
+    }
+
+    assert_eq!(consumed, segments.iter().map(|s| s.len()).sum::<usize>());
+}
+
+#[test]
+fn test_response_status_class() {
+    let classes = [
+        (100, StatusClass::Informational),
+        (200, StatusClass::Success),
+        (301, StatusClass::Redirection),
+        (404, StatusClass::ClientError),
+        (500, StatusClass::ServerError),
+        (600, StatusClass::Unknown),
+        (99, StatusClass::Unknown),
+    ];
+
+    for (status, class) in classes.iter() {
+
+        let mut response = Response::new();
+
+        response.status = *status;
+
+        assert_eq!(response.status_class(), *class, "{}", status);
+    }
+}
+
+#[test]
+fn test_response_parse() {
+    let data = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nhello";
+
+    let mut response = Response::new();
+
+    match response.parse(data) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(response.version(), b"1.1");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.reason(), b"OK");
+    assert_eq!(response.headers().len(), 2);
+    assert_eq!(response.body(), b"hello");
+
+    assert_eq!(
+        response.parse(b"XTTP/1.1 200 OK\r\n\r\n"),
+        Err(ParserError::Syntax { stage: Stage::StatusLine, kind: ErrorKind::Tag })
+    );
+    assert_eq!(
+        response.parse(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhel"),
+        Err(ParserError::IncompleteBody { expected: 5, got: 3 })
+    );
+}
+
+#[test]
+fn test_response_parse_for_method() {
+    let data = b"HTTP/1.1 200 OK\r\nContent-Length: 1024\r\n\r\n";
+
+    let mut response = Response::new();
+
+    assert_eq!(response.parse(data), Err(ParserError::IncompleteBody { expected: 1024, got: 0 }));
+
+    match response.parse_for_method(data, b"HEAD") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(response.status(), 200);
+    assert!(response.body().is_empty());
+
+    let data = b"HTTP/1.1 304 Not Modified\r\nETag: \"abc\"\r\nContent-Length: 1024\r\n\r\n";
+
+    match response.parse_for_method(data, b"GET") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(response.status(), 304);
+    assert_eq!(response.etag(), Some(ETag { weak: false, tag: b"abc" }));
+    assert!(response.body().is_empty());
+
+    assert_eq!(response.parse(b"HTTP/1.1 204\r\n\r\n"), Ok(()));
+    assert!(response.body().is_empty());
+}
+
+#[test]
+fn test_request_is_keep_alive() {
+    let cases = [
+        ("1.1", None, true),
+        ("1.1", Some("keep-alive"), true),
+        ("1.1", Some("close"), false),
+        ("1.1", Some("Upgrade, Close"), false),
+        ("1.0", None, false),
+        ("1.0", Some("Keep-Alive"), true),
+        ("1.0", Some("close"), false),
+        ("2", None, true),
+    ];
+
+    for (version, connection, keep_alive) in cases.iter() {
+
+        let connection = match connection {
+            Some(value) => format!("Connection: {}\r\n", value),
+            None => String::new()
+        };
+
+        let data = format!("GET / HTTP/{}\r\nHost: 127.0.0.1:9000\r\n{}\r\n", version, connection);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        match request.parse(data.as_bytes()) {
+            Ok(_) => {}
+            Err(e) => panic!("Something went wrong: {:?}", e)
+        }
+
+        assert_eq!(request.is_keep_alive(), *keep_alive, "HTTP/{} {}", version, connection);
+    }
+}
+
+#[test]
+fn test_request_proxy_connection() {
+    let data = "\
+        GET http://127.0.0.1:9000/ HTTP/1.0\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Proxy-Connection: keep-alive\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    let mut config = ParserConfig::new();
+    config.honor_proxy_connection = true;
+
+    assert_eq!(request.proxy_connection(), Some(&b"keep-alive"[..]));
+    assert!(!request.is_keep_alive());
+    assert!(request.is_keep_alive_with_config(&config));
+
+    // Connection takes precedence over Proxy-Connection
+    let data = "\
+        GET http://127.0.0.1:9000/ HTTP/1.0\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: close\r\n\
+        Proxy-Connection: keep-alive\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert!(!request.is_keep_alive_with_config(&config));
+}
+
+#[test]
+fn test_request_upgrade_protocols() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Connection: Upgrade\r\n\
+        Upgrade: HTTP/2.0, websocket ,\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.upgrade_protocols(), vec![&b"HTTP/2.0"[..], &b"websocket"[..]]);
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(b"GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n"), Ok(()));
+    assert!(request.upgrade_protocols().is_empty());
+}
+
+#[test]
+fn test_content_length() {
+    assert_eq!(http::parse::content_length(b"0"), Ok(0));
+    assert_eq!(http::parse::content_length(b"1024"), Ok(1024));
+    assert_eq!(http::parse::content_length(b"007"), Ok(7));
+    assert_eq!(http::parse::content_length(b""), Err(ParserError::ContentLength));
+    assert_eq!(http::parse::content_length(b"abc"), Err(ParserError::ContentLength));
+    assert_eq!(http::parse::content_length(b"+5"), Err(ParserError::ContentLength));
+    assert_eq!(http::parse::content_length(b" 5"), Err(ParserError::ContentLength));
+    assert_eq!(http::parse::content_length(b"-1"), Err(ParserError::ContentLength));
+    assert_eq!(http::parse::content_length(b"99999999999999999999999"), Err(ParserError::ContentLength));
+
+    let max = usize::MAX.to_string();
+
+    assert_eq!(http::parse::content_length(max.as_bytes()), Ok(usize::MAX));
+}
+
+#[test]
+fn test_chunk_size_overflow() {
+    assert_eq!(http::parse::chunk_size(b"1A\r\n"), Ok((&b"\r\n"[..], 26)));
+    assert!(http::parse::chunk_size(b"fffffffffffffffffffff\r\n").is_err());
+    assert!(http::parse::chunk_size(b"zz\r\n").is_err());
+
+    let chunks = [
+        ("a\r\n0123456789\r\n0\r\n\r\n", Ok(())),
+        ("fffffffffffffffffffff\r\nhello\r\n0\r\n\r\n", Err(ParserError::ChunkSizeOverflow)),
+        ("zz\r\nhello\r\n0\r\n\r\n", Err(ParserError::Body)),
+    ];
+
+    for (body, result) in chunks.iter() {
+
+        let data = format!("POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\n{}", body);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        assert_eq!(request.parse(data.as_bytes()), *result, "{:?}", body);
+        assert_eq!(request.parse_partial(data.as_bytes()).map(|_| ()), *result, "{:?}", body);
+    }
+}
+
+#[test]
+fn test_chunk_size_overflow_partial() {
+    let request = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffffffff\r\nhello";
+    let response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffffffff\r\nhello";
+
+    let mut config = ParserConfig::new();
+    config.max_body_bytes = usize::MAX;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut parsed = Request::new(&mut headers);
+
+    // A size whose chunk cannot fit in a usize is an error rather than a wait for more input
+    assert_eq!(parsed.parse_partial_with_config(request, &config), Err(ParserError::ChunkSizeOverflow));
+    assert_eq!(parsed.parse_partial(request), Err(ParserError::BodyTooLarge));
+
+    let mut parser = Parser::with_config(config);
+
+    assert_eq!(parser.feed(request), Err(ParserError::ChunkSizeOverflow));
+
+    let mut parsed = Response::new();
+
+    assert_eq!(parsed.parse_partial(response), Err(ParserError::ChunkSizeOverflow));
+    assert_eq!(Response::parse_all(response).map(|(responses, _)| responses.len()), Err(ParserError::ChunkSizeOverflow));
+}
+
+#[test]
+fn test_request_parse_partial_chunked_body_too_large() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+        5\r\n\
+    ";
+
+    let mut config = ParserConfig::new();
+    config.max_body_bytes = 8;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    // The second chunk is rejected on its size line, before its data arrives
+    assert_eq!(request.parse_partial_with_config(data.as_bytes(), &config), Err(ParserError::BodyTooLarge));
+
+    config.max_body_bytes = 10;
+
+    assert_eq!(request.parse_partial_with_config(data.as_bytes(), &config), Ok(Status::Partial));
+}
+
+#[test]
+fn test_request_incomplete_body() {
+    let data = b"POST / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nContent-Length: 10\r\n\r\nhello";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data), Err(ParserError::IncompleteBody { expected: 10, got: 5 }));
+    assert_eq!(request.parse_streaming(data, |_| {}), Err(ParserError::IncompleteBody { expected: 10, got: 5 }));
+    assert_eq!(
+        ParserError::IncompleteBody { expected: 10, got: 5 }.to_string(),
+        "ParserError: HTTP Message body has 5 of 10 bytes."
+    );
+}
+
+#[test]
+fn test_request_invalid_header_name() {
+    let mut test_header = http::header::Header::new();
+
+    assert_eq!(
+        http::parse::header(b"Bad Header: x\r\n", &mut test_header),
+        Err(Err::Failure((&b" Header: x\r\n"[..], ErrorKind::Char)))
+    );
+
+    let names = ["Bad Header: x", "Bad\x01Header: x", ": x", "Bad\x7f: x"];
+
+    for name in names.iter() {
+
+        let data = format!("GET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n{}\r\n\r\n", name);
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        assert_eq!(request.parse(data.as_bytes()), Err(ParserError::InvalidHeaderName), "{:?}", name);
+        assert_eq!(request.parse_partial(data.as_bytes()), Err(ParserError::InvalidHeaderName), "{:?}", name);
+    }
+}
+
+#[test]
+fn test_request_header_with_mode() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        x-signature: abc\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.header_with_mode("X-Signature", HeaderMatch::CaseInsensitive).map(|h| h.value()), Some(&b"abc"[..]));
+    assert_eq!(request.header_with_mode("X-Signature", HeaderMatch::Exact), None);
+    assert_eq!(request.header_with_mode("x-signature", HeaderMatch::Exact).map(|h| h.value()), Some(&b"abc"[..]));
+    assert_eq!(request.header("X-SIGNATURE"), request.header_with_mode("X-SIGNATURE", HeaderMatch::default()));
+}
+
+#[test]
+fn test_request_empty_valued_header() {
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        X-Empty:\r\n\
+        Accept: text/html\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.header_count(), 3);
+    assert_eq!(request.headers().len(), 3);
+    assert_eq!(request.headers()[1].name(), b"X-Empty");
+    assert_eq!(request.headers()[1].value(), b"");
+    assert_eq!(request.header("Accept").map(|h| h.value()), Some(&b"text/html"[..]));
+
+    // Headers left over from a longer request are not reported once the storage is reused
+    let data = "\
+        GET / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        \r\n\
+    ";
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.headers().len(), 1);
+    assert_eq!(request.header("Accept"), None);
+}
+
+#[test]
+fn test_parser_error_clone() {
+    let invalid = vec![b'a', 0xff];
+    let utf8_error = std::str::from_utf8(&invalid).unwrap_err();
+
+    let errors = vec![
+        ParserError::RequestLine,
+        ParserError::MethodTooLong,
+        ParserError::RequestTargetTooLong,
+        ParserError::InvalidVersion,
+        ParserError::Headers,
+        ParserError::TooManyHeaders,
+        ParserError::InvalidHeaderValue,
+        ParserError::InvalidHeaderName,
+        ParserError::HeaderTooLarge,
+        ParserError::HeadersBlockTooLarge,
+        ParserError::Body,
+        ParserError::BodyTooLarge,
+        ParserError::IncompleteBody { expected: 10, got: 5 },
+        ParserError::ChunkSizeOverflow,
+        ParserError::ContentLength,
+        ParserError::ConflictingFraming,
+        ParserError::ConflictingContentLength,
+        ParserError::TransferEncoding,
+        ParserError::UnsupportedTransferEncoding,
+        ParserError::InvalidUtf8Content(utf8_error),
+        ParserError::Decompression,
+        ParserError::InvalidAuthorization,
+        ParserError::InvalidPercentEncoding,
+        ParserError::Syntax { stage: Stage::Headers, kind: ErrorKind::Tag },
+        ParserError::Unknown,
+    ];
+
+    let cloned = errors.clone();
+
+    assert_eq!(cloned, errors);
+
+    for (error, clone) in errors.iter().zip(cloned.iter()) {
+        assert_eq!(clone.to_string(), error.to_string());
+    }
+}
+
+#[test]
+fn test_request_trailers() {
+    let data = "\
+        POST /upload HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: chunked\r\n\
+        Trailer: X-Checksum\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+        0\r\n\
+        X-Checksum: 5d41402a\r\n\
+        \r\n\
+    ";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    let declared = request.header("Trailer").map(|h| h.value()).unwrap();
+
+    assert_eq!(request.trailers().len(), 1);
+    assert!(request.trailers()[0].name_eq(declared));
+    assert_eq!(request.trailers()[0].value(), b"5d41402a");
+    assert_eq!(request.transfer_decoded_body(), Ok(b"hello".to_vec()));
+    assert_eq!(request.header("X-Checksum"), None);
+
+    // The input is partial until the empty line that ends the trailer fields
+    for split in data.find("0\r\n").unwrap()..data.len() {
+        assert_eq!(request.parse_partial(&data.as_bytes()[..split]), Ok(Status::Partial), "split at {}", split);
+    }
+
+    assert_eq!(request.parse_partial(data.as_bytes()), Ok(Status::Complete(data.len())));
+    assert_eq!(request.trailers().len(), 1);
+
+    request.reset();
+
+    assert!(request.trailers().is_empty());
+
+    // A chunked body without trailer fields has none
+    match request.parse(b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert!(request.trailers().is_empty());
+}
+
+#[test]
+fn test_message() {
+    let data = b"GET /status HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n";
+
+    let message = match http::parse::message(data) {
+        Ok(message) => message,
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    };
+
+    match message {
+        Message::Request(ref request) => {
+            assert_eq!(request.method(), b"GET");
+            assert_eq!(request.path(), b"/status");
+        },
+        Message::Response(_) => panic!("Expected a request")
+    }
+
+    assert!(message.response().is_none());
+
+    let data = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+
+    let message = match http::parse::message(data) {
+        Ok(message) => message,
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    };
+
+    assert!(message.request().is_none());
+    assert_eq!(message.response().map(|r| r.status()), Some(200));
+    assert_eq!(message.response().map(|r| r.body()), Some(&b"ok"[..]));
+
+    assert!(http::parse::message(b"HTTP/1.1 abc\r\n\r\n").is_err());
+    assert!(http::parse::message(b"\r\n").is_err());
+}
+
+#[test]
+fn test_parser_with_config() {
+    let data = b"POST /upload HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nX-Padding: aaaaaaaaaaaaaaaa\r\n\r\n";
+
+    let mut config = ParserConfig::new();
+    config.max_headers_block_bytes = 32;
+
+    let mut parser = Parser::with_config(config);
+
+    assert_eq!(parser.feed(data), Err(ParserError::HeadersBlockTooLarge));
+
+    // The request is read back with the same limits it was received with
+    let body = vec![b'a'; ParserConfig::default().max_body_bytes + 1];
+    let head = format!("POST /upload HTTP/1.1\r\nHost: 127.0.0.1:9000\r\nContent-Length: {}\r\n\r\n", body.len());
+
+    let mut config = ParserConfig::new();
+    config.max_body_bytes = body.len();
+
+    let mut parser = Parser::with_config(config);
+
+    assert_eq!(parser.feed(head.as_bytes()), Ok(Status::Partial));
+    assert_eq!(parser.feed(&body), Ok(Status::Complete(head.len() + body.len())));
+    assert_eq!(parser.request().map(|request| request.body().len()), Some(body.len()));
+}
+
+#[test]
+fn test_parser_request_line_too_long() {
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.feed(&vec![b'a'; 1024 * 1024]), Err(ParserError::MethodTooLong));
+
+    let mut config = ParserConfig::new();
+    config.max_request_target_bytes = 16;
+
+    let mut parser = Parser::with_config(config.clone());
+
+    assert_eq!(parser.feed(b"GET /0123456789abcdef HTTP/1.1"), Ok(Status::Partial));
+    assert_eq!(parser.feed(b"1111"), Err(ParserError::RequestTargetTooLong));
+
+    let mut parser = Parser::with_config(config);
+
+    assert_eq!(parser.feed(b"GET /0123456789abcdefg"), Ok(Status::Partial));
+    assert_eq!(parser.feed(&vec![b'a'; 1024 * 1024]), Err(ParserError::RequestTargetTooLong));
+}
+
+#[test]
+fn test_parser_chunked_body_too_large() {
+    let data = "\
+        POST / HTTP/1.1\r\n\
+        Host: 127.0.0.1:9000\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+    ";
+
+    let mut config = ParserConfig::new();
+    config.max_body_bytes = 8;
+
+    let mut parser = Parser::with_config(config);
+
+    assert_eq!(parser.feed(data.as_bytes()), Ok(Status::Partial));
+
+    // The limit counts every chunk received, across calls to feed
+    assert_eq!(parser.feed(b"5\r\n"), Err(ParserError::BodyTooLarge));
+}
+
+#[test]
+fn test_parser_leading_empty_line() {
+    let data = b"\r\nGET / HTTP/1.1\r\nHost: 127.0.0.1:9000\r\n\r\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse(data), Ok(()));
+
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.feed(data), Ok(Status::Complete(data.len())));
+    assert_eq!(parser.request().map(|request| request.header_count()), Some(1));
+}
+
+#[test]
+fn test_parser_headers_block_too_large() {
+    let mut config = ParserConfig::new();
+    config.max_headers_block_bytes = 50;
+
+    let mut parser = Parser::with_config(config);
+
+    assert_eq!(parser.feed(b"GET / HTTP/1.1\r\n"), Ok(Status::Partial));
+
+    // The limit is enforced line by line, before the empty line arrives
+    let mut result = Ok(Status::Partial);
+
+    for _ in 0..1000 {
+
+        result = parser.feed(b"X-A: b\r\n");
+
+        if result != Ok(Status::Partial) {
+            break;
+        }
+    }
+
+    assert_eq!(result, Err(ParserError::HeadersBlockTooLarge));
+    assert!(parser.consumed() < 100);
+}
+
+#[test]
+fn test_parser_http09() {
+    let data = b"GET /index.html\r\n";
+
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.feed(data), Err(ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::Tag }));
+
+    let mut config = ParserConfig::new();
+    config.allow_http09 = true;
+
+    let mut parser = Parser::with_config(config);
+
+    assert_eq!(parser.feed(data), Ok(Status::Complete(data.len())));
+    assert_eq!(parser.request().map(|request| request.version().to_vec()), Some(b"0.9".to_vec()));
+}
+
+#[test]
+fn test_request_bare_lf_round_trip() {
+    let data = "POST /upload HTTP/1.1\nHost: 127.0.0.1:9000\nTransfer-Encoding: chunked\n\n5;ext=\"a b\"\nhello\n0\nChecksum: 5d41402a\n\n";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.header_count(), 2);
+    assert_eq!(request.transfer_decoded_body(), Ok(b"hello".to_vec()));
+    assert_eq!(request.trailers()[0].value(), b"5d41402a");
+
+    // Serialized with CRLF line endings, the request parses back the same
+    let bytes = request.to_bytes();
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut reparsed = Request::new(&mut headers);
+
+    match reparsed.parse(&bytes) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(reparsed.path(), b"/upload");
+    assert_eq!(reparsed.headers(), request.headers());
+    assert_eq!(reparsed.body(), request.body());
+    assert_eq!(reparsed.transfer_decoded_body(), Ok(b"hello".to_vec()));
+}
+
+#[test]
+fn test_request_malformed_header_line() {
+    let data = [
+        "POST / HTTP/1.1\r\nHost: h\r\nfoo\r\nContent-Length: 5\r\n\r\nhello",
+        "POST / HTTP/1.1\r\nHost: h\r\n folded\r\nContent-Length: 5\r\n\r\nhello",
+    ];
+
+    // A line that is not a header must not end the block and leave the rest as the next request
+    for data in data.iter() {
+
+        let mut headers = [http::header::EMPTY_HEADER; 32];
+
+        let mut request = Request::new(&mut headers);
+
+        assert_eq!(request.parse(data.as_bytes()), Err(ParserError::Headers), "{:?}", data);
+        assert_eq!(request.parse_remaining(data.as_bytes()), Err(ParserError::Headers), "{:?}", data);
+        assert_eq!(request.parse_partial(data.as_bytes()), Err(ParserError::Headers), "{:?}", data);
+    }
+}
+
+#[test]
+fn test_request_reuse_clears_body() {
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(b"POST / HTTP/1.1\r\nHost: h\r\nContent-Length: 5\r\n\r\nhello") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.body(), b"hello");
+
+    match request.parse(b"GET / HTTP/1.1\r\nHost: h\r\n\r\n") {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert!(request.body().is_empty());
+}
+
+#[test]
+fn test_request_parse_partial_request_line_limits() {
+    let method = vec![b'a'; 100 * 1024];
+
+    let mut line = b"GET /".to_vec();
+    line.extend_from_slice(&method);
+
+    let mut config = ParserConfig::new();
+    config.max_request_target_bytes = 16;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    // An unterminated request line is bounded by the method and target limits
+    assert_eq!(request.parse_partial(&method), Err(ParserError::MethodTooLong));
+    assert_eq!(request.parse_partial(b"GET /index.html HTTP/1."), Ok(Status::Partial));
+    assert_eq!(request.parse_partial_with_config(&line, &config), Err(ParserError::RequestTargetTooLong));
+}
+
+#[test]
+fn test_request_parse_partial_http09() {
+    let data = b"GET /index.html\r\n";
+
+    let mut config = ParserConfig::new();
+    config.allow_http09 = true;
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    assert_eq!(request.parse_partial(data), Err(ParserError::Syntax { stage: Stage::RequestLine, kind: ErrorKind::Tag }));
+    assert_eq!(request.parse_partial_with_config(&data[..8], &config), Ok(Status::Partial));
+    assert_eq!(request.parse_partial_with_config(data, &config), Ok(Status::Complete(data.len())));
+    assert_eq!(request.version(), b"0.9");
+    assert_eq!(request.header_count(), 0);
+}
+
+#[test]
+fn test_header_trailing_whitespace() {
+    let mut test_header = http::header::Header::new();
+
+    assert_eq!(http::parse::header(b"Host: 127.0.0.1:9000 \t\r\n", &mut test_header), Ok((&b""[..], ())));
+    assert_eq!(test_header.value(), b"127.0.0.1:9000");
+
+    assert_eq!(http::parse::header(b"X-Spaced:  a  b  \r\n", &mut test_header), Ok((&b""[..], ())));
+    assert_eq!(test_header.value(), b"a  b");
+
+    // Typed accessors see the value without its padding
+    let data = "POST / HTTP/1.1\r\nHost: h\r\nContent-Length: 3 \r\n\r\nabc";
+
+    let mut headers = [http::header::EMPTY_HEADER; 32];
+
+    let mut request = Request::new(&mut headers);
+
+    match request.parse(data.as_bytes()) {
+        Ok(_) => {}
+        Err(e) => panic!("Something went wrong: {:?}", e)
+    }
+
+    assert_eq!(request.body(), b"abc");
+}